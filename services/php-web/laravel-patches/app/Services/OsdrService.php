@@ -9,13 +9,64 @@ class OsdrService extends RustApiService
 {
     /**
      * Получить список датасетов с пагинацией
+     * $params: sort, order, status, updated_since - фильтрация и сортировка на стороне rust_iss
      */
-    public function getList(int $page = 1, int $perPage = 20): array
+    public function getList(int $page = 1, int $perPage = 20, array $params = []): array
     {
-        return $this->get('/osdr/list', [
+        $allowed = array_intersect_key($params, array_flip(['sort', 'order', 'status', 'updated_since']));
+
+        return $this->get('/osdr/list', array_merge($allowed, [
             'page' => $page,
             'per_page' => $perPage
-        ]);
+        ]));
+    }
+
+    /**
+     * Получить датасет с нормализованными метаданными
+     */
+    public function getDataset(string $datasetId): array
+    {
+        return $this->get('/osdr/' . rawurlencode($datasetId));
+    }
+
+    /**
+     * Манифест файлов датасета (скачивание - /osdr/{id}/files/{fileId} в rust_iss)
+     */
+    public function getFiles(string $datasetId): array
+    {
+        return $this->get('/osdr/' . rawurlencode($datasetId) . '/files');
+    }
+
+    /**
+     * История версий датасета с отличиями между синхронизациями
+     */
+    public function getHistory(string $datasetId): array
+    {
+        return $this->get('/osdr/' . rawurlencode($datasetId) . '/history');
+    }
+
+    /**
+     * Новые и изменённые датасеты после $since (ISO 8601); $change: created | updated
+     */
+    public function getChanges(?string $since = null, ?string $change = null, int $limit = 100): array
+    {
+        return $this->get('/osdr/changes', array_filter([
+            'since' => $since,
+            'change' => $change,
+            'limit' => $limit
+        ], fn($v) => $v !== null));
+    }
+
+    /**
+     * Документы каталога, отклонённые нормализацией, с ошибками по полям
+     */
+    public function getQuarantine(?string $datasetId = null, int $page = 1, int $perPage = 50): array
+    {
+        return $this->get('/osdr/quarantine', array_filter([
+            'dataset_id' => $datasetId,
+            'page' => $page,
+            'per_page' => $perPage
+        ], fn($v) => $v !== null), false); // без кеша
     }
 
     /**
@@ -35,56 +86,32 @@ class OsdrService extends RustApiService
     }
 
     /**
-     * Фильтровать датасеты
+     * Полнотекстовый поиск по датасетам с подсветкой и фасетами
+     * $facets: organism, assay_type, factor
      */
-    public function filter(array $filters = []): array
+    public function search(string $q, int $page = 1, int $perPage = 20, array $facets = []): array
     {
-        $list = $this->getList();
-        $items = $list['data'] ?? [];
-
-        // Фильтр по типу
-        if (!empty($filters['type'])) {
-            $items = array_filter($items, function($item) use ($filters) {
-                return stripos($item['data_type'] ?? '', $filters['type']) !== false;
-            });
-        }
+        $allowed = array_intersect_key($facets, array_flip(['organism', 'assay_type', 'factor']));
 
-        // Фильтр по факторам
-        if (!empty($filters['factors'])) {
-            $items = array_filter($items, function($item) use ($filters) {
-                $factors = $item['factors_json'] ?? [];
-                if (!is_array($factors)) {
-                    $factors = json_decode($factors, true) ?? [];
-                }
-                return !empty(array_intersect($factors, (array)$filters['factors']));
-            });
-        }
-
-        // Поиск по названию
-        if (!empty($filters['search'])) {
-            $search = strtolower($filters['search']);
-            $items = array_filter($items, function($item) use ($search) {
-                $title = strtolower($item['title'] ?? '');
-                return stripos($title, $search) !== false;
-            });
-        }
-
-        return array_values($items);
+        return $this->get('/osdr/search', array_merge($allowed, [
+            'q' => $q,
+            'page' => $page,
+            'per_page' => $perPage
+        ]));
     }
 
     /**
-     * Сортировка датасетов
+     * Фильтровать датасеты
+     * $filters: sort, order, status, updated_since - список; search (+ organism, assay_type, factor) - поиск
      */
-    public function sort(array $items, string $field = 'created_at', string $direction = 'desc'): array
+    public function filter(array $filters = [], int $page = 1, int $perPage = 20): array
     {
-        usort($items, function($a, $b) use ($field, $direction) {
-            $aVal = $a[$field] ?? '';
-            $bVal = $b[$field] ?? '';
-            
-            $result = $aVal <=> $bVal;
-            return $direction === 'desc' ? -$result : $result;
-        });
+        if (!empty($filters['search'])) {
+            $result = $this->search($filters['search'], $page, $perPage, $filters);
+        } else {
+            $result = $this->getList($page, $perPage, $filters);
+        }
 
-        return $items;
+        return $result['data']['items'] ?? [];
     }
 }
@@ -1,4 +1,4 @@
-use reqwest::{Client, ClientBuilder};
+use reqwest::{header, Client, ClientBuilder, Response, StatusCode};
 use serde_json::Value;
 use std::time::Duration;
 use crate::domain::ApiError;
@@ -27,6 +27,25 @@ impl BaseClient {
         })
     }
 
+    /// Клиент для скачивания файлов: без общего таймаута (большие файлы), только на соединение;
+    /// без распаковки - тело и Range передаются как есть. Таймаут простоя при чтении тела -
+    /// на стороне потребителя потока (reqwest 0.11 не умеет read_timeout)
+    pub fn for_downloads(connect_timeout_secs: u64, max_retries: u32) -> Result<Self, ApiError> {
+        let client = ClientBuilder::new()
+            .connect_timeout(Duration::from_secs(connect_timeout_secs))
+            .user_agent("Cassiopeia-SpaceMonitor/1.0 (contact: burnfeniks@yandex.ru)")
+            .no_gzip()
+            .no_brotli()
+            .no_deflate()
+            .build()
+            .map_err(|e| ApiError::InternalError(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            max_retries,
+        })
+    }
+
     /// GET запрос с автоматическими ретраями
     /// Использует экспоненциальную задержку: 1s, 2s, 4s, 8s
     pub async fn get_json(&self, url: &str) -> Result<Value, ApiError> {
@@ -128,4 +147,90 @@ impl BaseClient {
             }
         }
     }
+
+    /// GET запрос с текстовым ответом (TLE в формате трёх строк и т.п.)
+    pub async fn get_text(&self, url: &str) -> Result<String, ApiError> {
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+
+            match self.client.get(url).send().await {
+                Ok(response) => {
+                    let status = response.status();
+
+                    if status.is_success() {
+                        let text = response.text().await?;
+                        info!("Successfully fetched text from {}", url);
+                        return Ok(text);
+                    } else if status.as_u16() == 429 && attempt <= self.max_retries {
+                        let backoff = 2u64.pow(attempt) * 2;
+                        warn!("Rate limited (429), retrying in {}s", backoff);
+                        tokio::time::sleep(Duration::from_secs(backoff)).await;
+                        continue;
+                    } else {
+                        return Err(ApiError::UpstreamError {
+                            code: format!("UPSTREAM_{}", status.as_u16()),
+                            message: format!("HTTP error: {}", status),
+                        });
+                    }
+                }
+                Err(e) if e.is_timeout() && attempt <= self.max_retries => {
+                    let backoff = 2u64.pow(attempt - 1);
+                    warn!("Timeout, retrying in {}s", backoff);
+                    tokio::time::sleep(Duration::from_secs(backoff)).await;
+                    continue;
+                }
+                Err(e) => {
+                    return Err(ApiError::from(e));
+                }
+            }
+        }
+    }
+
+    /// GET для потоковой передачи тела; `range` - заголовок Range клиента.
+    /// Успешным считается 2xx и 416 (ответ на невыполнимый Range передаётся клиенту как есть).
+    /// Ретраи - только до получения заголовков ответа; обрыв при чтении тела не повторяется
+    pub async fn get_stream(&self, url: &str, range: Option<&str>) -> Result<Response, ApiError> {
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+
+            let mut request = self.client.get(url).header(header::ACCEPT_ENCODING, "identity");
+            if let Some(range) = range {
+                request = request.header(header::RANGE, range);
+            }
+
+            match request.send().await {
+                Ok(response) => {
+                    let status = response.status();
+
+                    if status.is_success() || status == StatusCode::RANGE_NOT_SATISFIABLE {
+                        info!("Streaming from {} ({})", url, status);
+                        return Ok(response);
+                    } else if status.as_u16() == 429 && attempt <= self.max_retries {
+                        let backoff = 2u64.pow(attempt) * 2;
+                        warn!("Rate limited (429), retrying in {}s", backoff);
+                        tokio::time::sleep(Duration::from_secs(backoff)).await;
+                        continue;
+                    } else {
+                        return Err(ApiError::UpstreamError {
+                            code: format!("UPSTREAM_{}", status.as_u16()),
+                            message: format!("HTTP error: {}", status),
+                        });
+                    }
+                }
+                Err(e) if (e.is_timeout() || e.is_connect()) && attempt <= self.max_retries => {
+                    let backoff = 2u64.pow(attempt - 1);
+                    warn!("Connection failed, retrying in {}s", backoff);
+                    tokio::time::sleep(Duration::from_secs(backoff)).await;
+                    continue;
+                }
+                Err(e) => {
+                    return Err(ApiError::from(e));
+                }
+            }
+        }
+    }
 }
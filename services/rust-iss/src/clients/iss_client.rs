@@ -1,23 +1,81 @@
 use super::BaseClient;
-use crate::domain::ApiError;
-use serde_json::Value;
+use crate::domain::{satellite::ISS_NORAD_ID, ApiError};
+use serde_json::{json, Value};
 
-/// Клиент для работы с ISS tracking API
+/// Клиент для загрузки TLE отслеживаемых объектов (позиции - через IssPositionProvider)
 pub struct IssClient {
     base: BaseClient,
-    url: String,
+    tle_url: String,
+    // Шаблон URL для TLE прочих объектов, `{norad}` заменяется на NORAD ID
+    sat_tle_url: String,
 }
 
 impl IssClient {
-    pub fn new(url: String) -> Result<Self, ApiError> {
+    pub fn new(tle_url: String, sat_tle_url: String) -> Result<Self, ApiError> {
         // Таймаут 20 сек, до 3 ретраев
         let base = BaseClient::new(20, 3)?;
         
-        Ok(Self { base, url })
+        Ok(Self { base, tle_url, sat_tle_url })
     }
 
-    /// Получить текущую позицию МКС
-    pub async fn fetch_current_position(&self) -> Result<Value, ApiError> {
-        self.base.get_json(&self.url).await
+    /// Получить актуальный TLE объекта (JSON с полями name/line1/line2)
+    pub async fn fetch_tle(&self, norad_id: i32) -> Result<Value, ApiError> {
+        if norad_id == ISS_NORAD_ID {
+            return self.base.get_json(&self.tle_url).await;
+        }
+
+        let url = self.sat_tle_url.replace("{norad}", &norad_id.to_string());
+        let text = self.base.get_text(&url).await?;
+        parse_tle_text(&text).ok_or_else(|| ApiError::UpstreamError {
+            code: "INVALID_TLE".to_string(),
+            message: format!("No TLE for NORAD {} in response from {}", norad_id, url),
+        })
+    }
+}
+
+/// Разбор TLE в текстовом формате (необязательная строка имени + две строки элементов)
+fn parse_tle_text(text: &str) -> Option<Value> {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).filter(|l| !l.trim().is_empty()).collect();
+    let pos = lines.iter().position(|l| l.starts_with("1 "))?;
+    let line2 = lines.get(pos + 1).filter(|l| l.starts_with("2 "))?;
+    let name = pos.checked_sub(1).map(|i| lines[i].trim());
+
+    Some(json!({
+        "name": name,
+        "line1": lines[pos],
+        "line2": line2,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+    const LINE2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+    #[test]
+    fn parses_three_line_celestrak_text() {
+        let text = format!("ISS (ZARYA)             \r\n{}\r\n{}\r\n", LINE1, LINE2);
+        let tle = parse_tle_text(&text).unwrap();
+        assert_eq!(tle["name"], "ISS (ZARYA)");
+        assert_eq!(tle["line1"], LINE1);
+        assert_eq!(tle["line2"], LINE2);
+    }
+
+    #[test]
+    fn parses_two_line_text_without_name() {
+        let tle = parse_tle_text(&format!("\n{}\n{}\n", LINE1, LINE2)).unwrap();
+        assert!(tle["name"].is_null());
+        assert_eq!(tle["line1"], LINE1);
+        assert_eq!(tle["line2"], LINE2);
+    }
+
+    #[test]
+    fn rejects_malformed_text() {
+        assert!(parse_tle_text("").is_none());
+        assert!(parse_tle_text("No GP data found").is_none());
+        assert!(parse_tle_text(&format!("ISS (ZARYA)\n{}\n", LINE1)).is_none());
+        assert!(parse_tle_text(&format!("ISS (ZARYA)\n{}\n{}\n", LINE1, LINE1)).is_none());
     }
 }
@@ -0,0 +1,209 @@
+use super::BaseClient;
+use crate::domain::{iss::ProviderPosition, satellite::ISS_NORAD_ID, ApiError};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// Источник текущей позиции объекта. Провайдеры опрашиваются по порядку до первого успеха.
+#[async_trait]
+pub trait IssPositionProvider: Send + Sync {
+    /// Короткое имя провайдера (пишется в iss_fetch_log.provider)
+    fn name(&self) -> &'static str;
+
+    /// Умеет ли провайдер отдавать позицию этого объекта
+    fn supports(&self, norad_id: i32) -> bool;
+
+    /// Откуда фактически получена позиция (пишется в iss_fetch_log.source_url)
+    fn source_url(&self, norad_id: i32) -> String;
+
+    async fn fetch_position(&self, norad_id: i32) -> Result<ProviderPosition, ApiError>;
+}
+
+fn invalid_payload(provider: &str, field: &str) -> ApiError {
+    ApiError::UpstreamError {
+        code: "INVALID_POSITION".to_string(),
+        message: format!("{} response has no valid '{}'", provider, field),
+    }
+}
+
+fn number(value: &Value, key: &str) -> Option<f64> {
+    value.get(key).and_then(|v| {
+        v.as_f64().or_else(|| v.as_str().and_then(|s| s.trim().parse::<f64>().ok()))
+    })
+}
+
+fn timestamp(value: &Value, key: &str) -> Option<DateTime<Utc>> {
+    value
+        .get(key)
+        .and_then(|v| v.as_i64())
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+}
+
+/// Координаты в допустимых пределах (провайдеры иногда отдают мусор)
+fn checked_coords(provider: &str, latitude: Option<f64>, longitude: Option<f64>) -> Result<(f64, f64), ApiError> {
+    let latitude = latitude
+        .filter(|v| (-90.0..=90.0).contains(v))
+        .ok_or_else(|| invalid_payload(provider, "latitude"))?;
+    let longitude = longitude
+        .filter(|v| (-180.0..=180.0).contains(v))
+        .ok_or_else(|| invalid_payload(provider, "longitude"))?;
+    Ok((latitude, longitude))
+}
+
+/// Ответ wheretheiss.at: координаты, высота и скорость числами, видимость строкой
+fn parse_where_the_iss(provider: &str, raw: Value) -> Result<ProviderPosition, ApiError> {
+    let (latitude, longitude) = checked_coords(provider, number(&raw, "latitude"), number(&raw, "longitude"))?;
+
+    Ok(ProviderPosition {
+        latitude,
+        longitude,
+        altitude: number(&raw, "altitude"),
+        velocity: number(&raw, "velocity"),
+        visibility: raw.get("visibility").and_then(|v| v.as_str()).map(String::from),
+        timestamp: timestamp(&raw, "timestamp").unwrap_or_else(Utc::now),
+        raw,
+    })
+}
+
+/// Ответ open-notify: координаты строками в iss_position, статус в message
+fn parse_open_notify(provider: &str, raw: Value) -> Result<ProviderPosition, ApiError> {
+    if raw.get("message").and_then(|v| v.as_str()) != Some("success") {
+        return Err(invalid_payload(provider, "message"));
+    }
+    let position = raw
+        .get("iss_position")
+        .ok_or_else(|| invalid_payload(provider, "iss_position"))?;
+    let (latitude, longitude) =
+        checked_coords(provider, number(position, "latitude"), number(position, "longitude"))?;
+
+    Ok(ProviderPosition {
+        latitude,
+        longitude,
+        altitude: None,
+        velocity: None,
+        visibility: None,
+        timestamp: timestamp(&raw, "timestamp").unwrap_or_else(Utc::now),
+        raw,
+    })
+}
+
+/// api.wheretheiss.at: координаты, высота (км), скорость (км/ч), видимость
+pub struct WhereTheIssProvider {
+    base: BaseClient,
+    url: String,
+}
+
+impl WhereTheIssProvider {
+    pub fn new(url: String) -> Result<Self, ApiError> {
+        // Таймаут 20 сек, до 3 ретраев
+        let base = BaseClient::new(20, 3)?;
+        Ok(Self { base, url })
+    }
+}
+
+#[async_trait]
+impl IssPositionProvider for WhereTheIssProvider {
+    fn name(&self) -> &'static str {
+        "wheretheiss"
+    }
+
+    /// URL провайдера настроен на МКС
+    fn supports(&self, norad_id: i32) -> bool {
+        norad_id == ISS_NORAD_ID
+    }
+
+    fn source_url(&self, _norad_id: i32) -> String {
+        self.url.clone()
+    }
+
+    async fn fetch_position(&self, _norad_id: i32) -> Result<ProviderPosition, ApiError> {
+        let raw = self.base.get_json(&self.url).await?;
+        parse_where_the_iss(self.name(), raw)
+    }
+}
+
+/// api.open-notify.org/iss-now.json: только координаты (строками) и время
+pub struct OpenNotifyProvider {
+    base: BaseClient,
+    url: String,
+}
+
+impl OpenNotifyProvider {
+    pub fn new(url: String) -> Result<Self, ApiError> {
+        // Таймаут 10 сек, без ретраев - это резервный источник
+        let base = BaseClient::new(10, 0)?;
+        Ok(Self { base, url })
+    }
+}
+
+#[async_trait]
+impl IssPositionProvider for OpenNotifyProvider {
+    fn name(&self) -> &'static str {
+        "open_notify"
+    }
+
+    /// URL провайдера настроен на МКС
+    fn supports(&self, norad_id: i32) -> bool {
+        norad_id == ISS_NORAD_ID
+    }
+
+    fn source_url(&self, _norad_id: i32) -> String {
+        self.url.clone()
+    }
+
+    async fn fetch_position(&self, _norad_id: i32) -> Result<ProviderPosition, ApiError> {
+        let raw = self.base.get_json(&self.url).await?;
+        parse_open_notify(self.name(), raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn open_notify_payload_uses_where_the_iss_keys() {
+        let raw = json!({
+            "message": "success",
+            "timestamp": 1_700_000_000,
+            "iss_position": {"latitude": "-51.2345", "longitude": "120.5"}
+        });
+        let payload = parse_open_notify("open_notify", raw.clone()).unwrap().to_payload("open_notify");
+
+        assert_eq!(payload["provider"], "open_notify");
+        assert_eq!(payload["latitude"], -51.2345);
+        assert_eq!(payload["longitude"], 120.5);
+        assert_eq!(payload["timestamp"], 1_700_000_000);
+        assert!(payload["altitude"].is_null() && payload["velocity"].is_null() && payload["visibility"].is_null());
+        assert_eq!(payload["raw"], raw);
+    }
+
+    #[test]
+    fn where_the_iss_payload_keeps_numbers() {
+        let raw = json!({
+            "latitude": 12.5, "longitude": -33.25, "altitude": 418.2,
+            "velocity": 27580.4, "visibility": "daylight", "timestamp": 1_700_000_060
+        });
+        let payload = parse_where_the_iss("wheretheiss", raw).unwrap().to_payload("wheretheiss");
+
+        assert_eq!(payload["latitude"], 12.5);
+        assert_eq!(payload["longitude"], -33.25);
+        assert_eq!(payload["altitude"], 418.2);
+        assert_eq!(payload["velocity"], 27580.4);
+        assert_eq!(payload["visibility"], "daylight");
+        assert_eq!(payload["timestamp"], 1_700_000_060);
+    }
+
+    #[test]
+    fn rejects_failed_or_out_of_range_payloads() {
+        let failed = json!({"message": "error", "iss_position": {"latitude": "1", "longitude": "2"}});
+        assert!(parse_open_notify("open_notify", failed).is_err());
+        let missing = json!({"message": "success", "timestamp": 1});
+        assert!(parse_open_notify("open_notify", missing).is_err());
+        let out_of_range = json!({"latitude": 91.0, "longitude": 0.0});
+        assert!(parse_where_the_iss("wheretheiss", out_of_range).is_err());
+        let garbage = json!({"latitude": "north", "longitude": 10.0});
+        assert!(parse_where_the_iss("wheretheiss", garbage).is_err());
+    }
+}
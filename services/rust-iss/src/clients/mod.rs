@@ -1,10 +1,12 @@
 // Clients layer - работа с внешними API
 pub mod iss_client;
+pub mod iss_providers;
 pub mod nasa_client;
 pub mod spacex_client;
 pub mod base_client;
 
 pub use iss_client::IssClient;
+pub use iss_providers::{IssPositionProvider, OpenNotifyProvider, WhereTheIssProvider};
 pub use nasa_client::NasaClient;
 pub use spacex_client::SpacexClient;
 pub use base_client::BaseClient;
@@ -1,11 +1,16 @@
 use super::BaseClient;
-use crate::domain::ApiError;
+use crate::domain::{
+    osdr::{parse_catalogue_page, OsdrCataloguePage, OsdrCursor, OsdrWalk},
+    ApiError,
+};
 use chrono::Utc;
 use serde_json::Value;
 
 /// Клиент для работы с NASA API (OSDR, APOD, NeoWs, DONKI)
 pub struct NasaClient {
     base: BaseClient,
+    // Скачивание файлов OSDR (без общего таймаута)
+    downloads: BaseClient,
     api_key: String,
 }
 
@@ -13,8 +18,9 @@ impl NasaClient {
     pub fn new(api_key: String) -> Result<Self, ApiError> {
         // Таймаут 30 сек, до 3 ретраев
         let base = BaseClient::new(30, 3)?;
+        let downloads = BaseClient::for_downloads(15, 2)?;
         
-        Ok(Self { base, api_key })
+        Ok(Self { base, downloads, api_key })
     }
 
     /// Получить данные OSDR
@@ -22,6 +28,24 @@ impl NasaClient {
         self.base.get_json(url).await
     }
 
+    /// Постраничный обход каталога OSDR с позиции `start`, не больше `max_pages` страниц
+    pub fn walk_osdr(&self, start: OsdrCursor, max_pages: u32) -> OsdrCatalogueWalker<'_> {
+        OsdrCatalogueWalker {
+            client: self,
+            walk: OsdrWalk::new(start, max_pages),
+        }
+    }
+
+    /// Список файлов датасета OSDR
+    pub async fn fetch_osdr_files(&self, url: &str) -> Result<Value, ApiError> {
+        self.base.get_json(url).await
+    }
+
+    /// Скачать файл OSDR потоком (с заголовком Range клиента)
+    pub async fn download(&self, url: &str, range: Option<&str>) -> Result<reqwest::Response, ApiError> {
+        self.downloads.get_stream(url, range).await
+    }
+
     /// Получить APOD (Astronomy Picture of the Day)
     pub async fn fetch_apod(&self) -> Result<Value, ApiError> {
         let url = "https://api.nasa.gov/planetary/apod";
@@ -95,3 +119,30 @@ impl NasaClient {
         (from.to_string(), to.to_string())
     }
 }
+
+/// Обход каталога OSDR по ссылкам на следующую страницу
+pub struct OsdrCatalogueWalker<'a> {
+    client: &'a NasaClient,
+    walk: OsdrWalk,
+}
+
+impl OsdrCatalogueWalker<'_> {
+    /// Следующая страница; None - каталог пройден или исчерпан лимит страниц.
+    /// При ошибке позиция остаётся на той же странице (см. resume_cursor).
+    pub async fn next_page(&mut self) -> Result<Option<(OsdrCursor, OsdrCataloguePage)>, ApiError> {
+        let Some(cursor) = self.walk.pending().cloned() else {
+            return Ok(None);
+        };
+
+        let json = self.client.fetch_osdr(&cursor.url).await?;
+        let page = parse_catalogue_page(json, &cursor.url);
+        self.walk.advance(&page);
+
+        Ok(Some((cursor, page)))
+    }
+
+    /// Страница, с которой продолжить обход; None - каталог пройден до конца
+    pub fn resume_cursor(&self) -> Option<&OsdrCursor> {
+        self.walk.resume_cursor()
+    }
+}
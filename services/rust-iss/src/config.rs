@@ -5,16 +5,38 @@ pub struct AppConfig {
     pub database_url: String,
     pub nasa_api_url: String,
     pub nasa_api_key: String,
+    // Шаблон URL списка файлов датасета OSDR ({id} - номер без "OSD-", {dataset_id} - полностью)
+    pub osdr_files_url: String,
     pub where_iss_url: String,
+    pub iss_tle_url: String,
+    // Шаблон URL TLE для прочих отслеживаемых объектов ({norad} - NORAD ID)
+    pub sat_tle_url: String,
+    pub open_notify_url: String,
+    // Порядок провайдеров позиции МКС (wheretheiss, open_notify, tle)
+    pub iss_providers: Vec<String>,
     
     // Интервалы опроса (в секундах)
     pub fetch_every_osdr: u64,
     pub fetch_every_iss: u64,
+    pub fetch_every_tle: u64,
     pub fetch_every_apod: u64,
     pub fetch_every_neo: u64,
     pub fetch_every_donki: u64,
     pub fetch_every_spacex: u64,
     
+    // Размер страницы /osdr/list по умолчанию
+    pub osdr_list_limit: i64,
+    // Сколько манифестов файлов OSDR загружать за одну синхронизацию
+    pub osdr_files_per_sync: i64,
+    // Максимум страниц каталога OSDR за одну синхронизацию
+    pub osdr_max_pages: u32,
+    
+    // Сколько дней хранить журнал запусков sync_runs
+    pub sync_runs_retention_days: i64,
+    
+    // Ёмкость канала push-уведомлений о позициях МКС (SSE/WebSocket)
+    pub iss_stream_capacity: usize,
+    
     // Redis настройки
     pub redis_url: Option<String>,
     pub redis_ttl: u64,
@@ -35,16 +57,44 @@ impl AppConfig {
             nasa_api_key: env::var("NASA_API_KEY")
                 .unwrap_or_default(),
             
+            osdr_files_url: env::var("OSDR_FILES_URL")
+                .unwrap_or_else(|_| "https://osdr.nasa.gov/osdr/data/osd/files/{id}".to_string()),
+            
             where_iss_url: env::var("WHERE_ISS_URL")
                 .unwrap_or_else(|_| "https://api.wheretheiss.at/v1/satellites/25544".to_string()),
             
+            iss_tle_url: env::var("ISS_TLE_URL")
+                .unwrap_or_else(|_| "https://api.wheretheiss.at/v1/satellites/25544/tles".to_string()),
+            
+            sat_tle_url: env::var("SAT_TLE_URL")
+                .unwrap_or_else(|_| "https://celestrak.org/NORAD/elements/gp.php?CATNR={norad}&FORMAT=TLE".to_string()),
+            
+            open_notify_url: env::var("OPEN_NOTIFY_URL")
+                .unwrap_or_else(|_| "http://api.open-notify.org/iss-now.json".to_string()),
+            
+            iss_providers: env::var("ISS_PROVIDERS")
+                .unwrap_or_else(|_| "wheretheiss,open_notify,tle".to_string())
+                .split(',')
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            
             fetch_every_osdr: env_u64("FETCH_EVERY_SECONDS", 600),
             fetch_every_iss: env_u64("ISS_EVERY_SECONDS", 120),
+            fetch_every_tle: env_u64("TLE_EVERY_SECONDS", 21600), // 6ч
             fetch_every_apod: env_u64("APOD_EVERY_SECONDS", 43200), // 12ч
             fetch_every_neo: env_u64("NEO_EVERY_SECONDS", 7200),   // 2ч
             fetch_every_donki: env_u64("DONKI_EVERY_SECONDS", 3600), // 1ч
             fetch_every_spacex: env_u64("SPACEX_EVERY_SECONDS", 3600),
             
+            osdr_list_limit: env_u64("OSDR_LIST_LIMIT", 20) as i64,
+            osdr_files_per_sync: env_u64("OSDR_FILES_PER_SYNC", 50) as i64,
+            osdr_max_pages: env_u64("OSDR_MAX_PAGES", 50).max(1) as u32,
+            
+            sync_runs_retention_days: env_u64("SYNC_RUNS_RETENTION_DAYS", 30).max(1) as i64,
+            
+            iss_stream_capacity: env_u64("ISS_STREAM_CAPACITY", 64) as usize,
+            
             redis_url: env::var("REDIS_URL").ok(),
             redis_ttl: env_u64("REDIS_TTL_SECONDS", 300), // 5 мин по умолчанию
             
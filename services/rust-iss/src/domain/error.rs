@@ -47,18 +47,38 @@ impl fmt::Display for ApiError {
     }
 }
 
+impl ApiError {
+    /// Код ошибки в ответе API
+    pub fn code(&self) -> &str {
+        match self {
+            ApiError::DatabaseError(_) => "DATABASE_ERROR",
+            ApiError::UpstreamError { code, .. } => code.as_str(),
+            ApiError::ValidationError(_) => "VALIDATION_ERROR",
+            ApiError::NotFound(_) => "NOT_FOUND",
+            ApiError::RateLimitExceeded => "RATE_LIMIT_EXCEEDED",
+            ApiError::InternalError(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    /// Сообщение в ответе API (без префикса Display)
+    pub fn message(&self) -> String {
+        match self {
+            ApiError::DatabaseError(msg)
+            | ApiError::UpstreamError { message: msg, .. }
+            | ApiError::ValidationError(msg)
+            | ApiError::NotFound(msg)
+            | ApiError::InternalError(msg) => msg.clone(),
+            ApiError::RateLimitExceeded => "Too many requests".to_string(),
+        }
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let trace_id = Uuid::new_v4().to_string();
         
-        let (code, message) = match &self {
-            ApiError::DatabaseError(msg) => ("DATABASE_ERROR", msg.clone()),
-            ApiError::UpstreamError { code, message } => (code.as_str(), message.clone()),
-            ApiError::ValidationError(msg) => ("VALIDATION_ERROR", msg.clone()),
-            ApiError::NotFound(msg) => ("NOT_FOUND", msg.clone()),
-            ApiError::RateLimitExceeded => ("RATE_LIMIT_EXCEEDED", "Too many requests".to_string()),
-            ApiError::InternalError(msg) => ("INTERNAL_ERROR", msg.clone()),
-        };
+        let code = self.code();
+        let message = self.message();
 
         tracing::error!("API Error [{}]: {} - {}", trace_id, code, message);
 
@@ -99,6 +119,12 @@ impl<T> ApiResponse<T> {
     }
 }
 
+impl From<crate::domain::iss::orbit::OrbitError> for ApiError {
+    fn from(err: crate::domain::iss::orbit::OrbitError) -> Self {
+        ApiError::InternalError(err.to_string())
+    }
+}
+
 impl From<sqlx::Error> for ApiError {
     fn from(err: sqlx::Error) -> Self {
         ApiError::DatabaseError(err.to_string())
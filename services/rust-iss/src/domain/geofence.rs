@@ -0,0 +1,182 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::domain::iss::haversine_km;
+
+/// Форма геозоны. Полигон задаётся кольцом [долгота, широта] и не должен пересекать антимеридиан.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GeofenceShape {
+    Polygon { coordinates: Vec<[f64; 2]> },
+    Circle { latitude: f64, longitude: f64, radius_km: f64 },
+}
+
+impl GeofenceShape {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            GeofenceShape::Polygon { coordinates } => {
+                if coordinates.len() < 3 {
+                    return Err("polygon must have at least 3 points".to_string());
+                }
+                for [lon, lat] in coordinates {
+                    check_point(*lat, *lon)?;
+                }
+                Ok(())
+            }
+            GeofenceShape::Circle { latitude, longitude, radius_km } => {
+                check_point(*latitude, *longitude)?;
+                if !(*radius_km > 0.0 && *radius_km <= 20_000.0) {
+                    return Err("radius_km must be in (0, 20000]".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Попадает ли точка в геозону
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        match self {
+            GeofenceShape::Polygon { coordinates } => point_in_ring(coordinates, latitude, longitude),
+            GeofenceShape::Circle { latitude: clat, longitude: clon, radius_km } => {
+                haversine_km(*clat, *clon, latitude, longitude) <= *radius_km
+            }
+        }
+    }
+}
+
+/// Точка внутри кольца [долгота, широта] (метод луча по плоскости долгота/широта)
+pub fn point_in_ring(ring: &[[f64; 2]], latitude: f64, longitude: f64) -> bool {
+    if ring.is_empty() {
+        return false;
+    }
+    let mut inside = false;
+    let mut j = ring.len() - 1;
+    for i in 0..ring.len() {
+        let [xi, yi] = ring[i];
+        let [xj, yj] = ring[j];
+        if (yi > latitude) != (yj > latitude)
+            && longitude < (xj - xi) * (latitude - yi) / (yj - yi) + xi
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+fn check_point(latitude: f64, longitude: f64) -> Result<(), String> {
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return Err(format!("point ({}, {}) is out of range", latitude, longitude));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Geofence {
+    pub id: i64,
+    pub name: String,
+    pub shape: GeofenceShape,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Geofence {
+    pub fn from_row(row: &sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        let shape: serde_json::Value = row.try_get("shape")?;
+        Ok(Self {
+            id: row.get("id"),
+            name: row.get("name"),
+            shape: serde_json::from_value(shape).map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+}
+
+/// Тело запроса на создание/изменение геозоны
+#[derive(Debug, Deserialize)]
+pub struct GeofenceInput {
+    pub name: String,
+    pub shape: GeofenceShape,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeofenceTransition {
+    Enter,
+    Exit,
+}
+
+impl GeofenceTransition {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GeofenceTransition::Enter => "enter",
+            GeofenceTransition::Exit => "exit",
+        }
+    }
+
+    /// Переход между двумя соседними замерами (None - граница не пересекалась)
+    pub fn between(was_inside: bool, is_inside: bool) -> Option<Self> {
+        match (was_inside, is_inside) {
+            (false, true) => Some(GeofenceTransition::Enter),
+            (true, false) => Some(GeofenceTransition::Exit),
+            _ => None,
+        }
+    }
+}
+
+/// Событие входа/выхода МКС в геозону
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeofenceEvent {
+    pub id: i64,
+    pub geofence_id: i64,
+    pub geofence_name: String,
+    pub event_type: String,
+    pub occurred_at: DateTime<Utc>,
+    pub position_id: Option<i64>,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl GeofenceEvent {
+    pub fn from_row(row: &sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(Self {
+            id: row.try_get("id")?,
+            geofence_id: row.try_get("geofence_id")?,
+            geofence_name: row.try_get("geofence_name")?,
+            event_type: row.try_get("event_type")?,
+            occurred_at: row.try_get("occurred_at")?,
+            position_id: row.try_get("position_id")?,
+            latitude: row.try_get("latitude")?,
+            longitude: row.try_get("longitude")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polygon_and_circle_membership() {
+        let square = GeofenceShape::Polygon {
+            coordinates: vec![[30.0, 50.0], [40.0, 50.0], [40.0, 60.0], [30.0, 60.0]],
+        };
+        assert!(square.contains(55.0, 35.0));
+        assert!(!square.contains(45.0, 35.0));
+
+        let station = GeofenceShape::Circle { latitude: 55.7558, longitude: 37.6176, radius_km: 100.0 };
+        assert!(station.contains(56.0, 37.6));
+        assert!(!station.contains(59.9, 30.3));
+    }
+
+    #[test]
+    fn transition_only_on_boundary_crossing() {
+        assert_eq!(GeofenceTransition::between(false, true), Some(GeofenceTransition::Enter));
+        assert_eq!(GeofenceTransition::between(true, false), Some(GeofenceTransition::Exit));
+        assert_eq!(GeofenceTransition::between(true, true), None);
+    }
+}
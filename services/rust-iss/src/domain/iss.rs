@@ -1,21 +1,39 @@
+pub mod altitude;
+pub mod ground_track;
+pub mod orbit;
+pub mod passes;
+
 use chrono::{DateTime, Utc};
+use crate::domain::region::{self, Region};
+use crate::domain::solar::SubsolarPoint;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IssPosition {
     pub id: i64,
+    /// NORAD ID объекта (25544 - МКС)
+    pub norad_id: i32,
     pub fetched_at: DateTime<Utc>,
     pub source_url: String,
+    /// Провайдер, от которого получена позиция (wheretheiss, open_notify, tle)
+    pub provider: Option<String>,
     pub latitude: Option<f64>,
     pub longitude: Option<f64>,
     pub altitude: Option<f64>,
     pub velocity: Option<f64>,
+    pub visibility: Option<String>,
+    /// Время позиции по данным провайдера (поле `timestamp`)
+    pub position_at: Option<DateTime<Utc>>,
+    /// Страна/море/океан под МКС (офлайн, по встроенным контурам)
+    #[serde(default)]
+    pub region: Option<Region>,
     pub payload: Value,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct IssTrend {
+    // Последние два замера (совместимость со старым форматом)
     pub movement: bool,
     pub delta_km: f64,
     pub dt_sec: f64,
@@ -26,29 +44,279 @@ pub struct IssTrend {
     pub from_lon: Option<f64>,
     pub to_lat: Option<f64>,
     pub to_lon: Option<f64>,
+
+    // Анализ по окну ?hours=N
+    pub window_hours: i64,
+    pub window_from: DateTime<Utc>,
+    pub window_to: DateTime<Utc>,
+    pub samples: usize,
+    /// В окне больше замеров, чем загружается для анализа: статистика - только по самым новым
+    pub truncated: bool,
+    /// Время первого замера, вошедшего в анализ (при truncated - позже window_from)
+    pub covered_from: Option<DateTime<Utc>>,
+    /// Суммарная длина наземной трассы между соседними замерами
+    pub total_distance_km: f64,
+    pub velocity_stats: Option<IssStats>,
+    pub altitude_stats: Option<IssStats>,
+    /// Наклон МНК-прямой высоты по времени, км/ч
+    pub altitude_rate_km_h: Option<f64>,
+    /// Порог разрыва: 3 медианных интервала между замерами
+    pub gap_threshold_sec: Option<f64>,
+    pub gaps: Vec<IssGap>,
+}
+
+/// Среднее/минимум/максимум величины за окно
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssStats {
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Разрыв в ряду замеров (пропущенные опросы)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssGap {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub duration_sec: f64,
+}
+
+impl IssStats {
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let sum: f64 = values.iter().sum();
+        Some(Self {
+            mean: sum / values.len() as f64,
+            min: values.iter().cloned().fold(f64::INFINITY, f64::min),
+            max: values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+}
+
+/// Страница истории позиций МКС (GET /iss/history, GET /last?limit=N)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssHistory {
+    pub items: Vec<IssPosition>,
+    pub limit: i64,
+    /// Передать как `cursor` для следующей страницы; None - история закончилась
+    pub next_cursor: Option<i64>,
+}
+
+/// Выборка позиций МКС за период (GET /iss/range)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssRange {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub items: Vec<IssPosition>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    /// Шаг прореживания в секундах (None - без прореживания)
+    pub step_sec: Option<i64>,
+}
+
+/// Расчётная точка орбиты (SGP4 по TLE)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssTrackPoint {
+    pub at: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Высота над эллипсоидом WGS-84, км
+    pub altitude: f64,
+    /// Инерциальная скорость, км/ч
+    pub velocity: f64,
+}
+
+/// Сведения о наборе TLE, по которому выполнен расчёт
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TleInfo {
+    pub name: Option<String>,
+    pub norad_id: u32,
+    pub epoch: DateTime<Utc>,
+    pub line1: String,
+    pub line2: String,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Прогноз положения МКС на момент времени (GET /iss/predict)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssPrediction {
+    #[serde(flatten)]
+    pub point: IssTrackPoint,
+    /// Удалённость момента от эпохи TLE, ч (точность SGP4 падает с возрастом)
+    pub tle_age_hours: f64,
+    pub tle: TleInfo,
+}
+
+/// Расчётная трасса МКС за период (GET /iss/track)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssTrack {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub step_sec: i64,
+    pub points: Vec<IssTrackPoint>,
+    pub tle: TleInfo,
+}
+
+/// Наблюдатель для расчёта пролётов
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Observer {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Высота над уровнем моря, м
+    pub altitude_m: f64,
+}
+
+/// Пролёты МКС над наблюдателем (GET /iss/passes)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssPasses {
+    pub observer: Observer,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub passes: Vec<passes::Pass>,
+    pub tle: TleInfo,
+}
+
+/// Освещённость объекта Солнцем (GET /iss/illumination)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssIllumination {
+    pub norad_id: i32,
+    pub at: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// км
+    pub altitude: f64,
+    /// Объект вне тени Земли
+    pub sunlit: bool,
+    /// "daylight" / "eclipsed", как у wheretheiss.at
+    pub visibility: String,
+    /// Высота Солнца над горизонтом в подспутниковой точке, градусы (< 0 - на земле ночь)
+    pub sun_elevation: f64,
+    pub subsolar: SubsolarPoint,
+    /// "tle" - положение по SGP4, "coordinates" - переданные координаты
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tle: Option<TleInfo>,
+}
+
+impl IssTrackPoint {
+    /// Подспутниковая точка по SGP4 на момент `at`
+    pub fn propagate(sgp4: &orbit::Sgp4, at: DateTime<Utc>) -> Result<Self, orbit::OrbitError> {
+        let state = sgp4.propagate_at(at)?;
+        let geo = orbit::subpoint(&state, at);
+        Ok(Self {
+            at,
+            latitude: geo.latitude,
+            longitude: geo.longitude,
+            altitude: geo.altitude_km,
+            velocity: state.speed_km_s() * 3600.0,
+        })
+    }
 }
 
 impl IssPosition {
+    /// Типизированные колонки имеют приоритет; для строк до бэкфилла - разбор payload
     pub fn from_row(row: &sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
         use sqlx::Row;
         
         let payload: Value = row.try_get("payload")?;
         
-        Ok(Self {
+        let mut position = Self {
             id: row.get("id"),
+            norad_id: row.try_get("norad_id")?,
             fetched_at: row.get("fetched_at"),
             source_url: row.get("source_url"),
-            latitude: Self::extract_number(&payload, "latitude"),
-            longitude: Self::extract_number(&payload, "longitude"),
-            altitude: Self::extract_number(&payload, "altitude"),
-            velocity: Self::extract_number(&payload, "velocity"),
+            provider: row.try_get::<Option<String>, _>("provider")?
+                .or_else(|| Self::extract_string(&payload, "provider")),
+            latitude: row.try_get::<Option<f64>, _>("latitude")?
+                .or_else(|| Self::extract_number(&payload, "latitude")),
+            longitude: row.try_get::<Option<f64>, _>("longitude")?
+                .or_else(|| Self::extract_number(&payload, "longitude")),
+            altitude: row.try_get::<Option<f64>, _>("altitude")?
+                .or_else(|| Self::extract_number(&payload, "altitude")),
+            velocity: row.try_get::<Option<f64>, _>("velocity")?
+                .or_else(|| Self::extract_number(&payload, "velocity")),
+            visibility: row.try_get::<Option<String>, _>("visibility")?
+                .or_else(|| Self::extract_string(&payload, "visibility")),
+            position_at: row.try_get::<Option<DateTime<Utc>>, _>("position_at")?
+                .or_else(|| Self::extract_timestamp(&payload, "timestamp")),
+            region: None,
             payload,
-        })
+        };
+        position.region = match (position.latitude, position.longitude) {
+            (Some(lat), Some(lon)) => region::locate(lat, lon),
+            _ => None,
+        };
+
+        Ok(position)
     }
 
-    fn extract_number(value: &Value, key: &str) -> Option<f64> {
+    pub fn extract_number(value: &Value, key: &str) -> Option<f64> {
         value.get(key).and_then(|v| {
-            v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse::<f64>().ok()))
+            v.as_f64().or_else(|| v.as_str().and_then(|s| s.trim().parse::<f64>().ok()))
         })
     }
+
+    pub fn extract_string(value: &Value, key: &str) -> Option<String> {
+        value
+            .get(key)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+    }
+
+    /// Unix-время в секундах (число или строка)
+    pub fn extract_timestamp(value: &Value, key: &str) -> Option<DateTime<Utc>> {
+        let secs = value.get(key).and_then(|v| {
+            v.as_i64().or_else(|| v.as_str().and_then(|s| s.trim().parse::<i64>().ok()))
+        })?;
+        DateTime::from_timestamp(secs, 0)
+    }
+}
+
+/// Позиция от провайдера, приведённая к единой схеме
+#[derive(Debug, Clone)]
+pub struct ProviderPosition {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// км
+    pub altitude: Option<f64>,
+    /// км/ч
+    pub velocity: Option<f64>,
+    pub visibility: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    /// Исходный ответ провайдера
+    pub raw: Value,
+}
+
+impl ProviderPosition {
+    /// Нормализованный payload для iss_fetch_log (ключи совпадают с форматом wheretheiss.at)
+    pub fn to_payload(&self, provider: &str) -> Value {
+        serde_json::json!({
+            "provider": provider,
+            "latitude": self.latitude,
+            "longitude": self.longitude,
+            "altitude": self.altitude,
+            "velocity": self.velocity,
+            "visibility": self.visibility,
+            "timestamp": self.timestamp.timestamp(),
+            "raw": self.raw,
+        })
+    }
+}
+
+/// Формула гаверсинуса для вычисления расстояния между двумя точками на сфере
+pub fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let rlat1 = lat1.to_radians();
+    let rlat2 = lat2.to_radians();
+    let dlat = (lat2 - lat1).to_radians();
+    let dlon = (lon2 - lon1).to_radians();
+    
+    let a = (dlat / 2.0).sin().powi(2) 
+        + rlat1.cos() * rlat2.cos() * (dlon / 2.0).sin().powi(2);
+    let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
+    
+    6371.0 * c // Радиус Земли в км
 }
@@ -0,0 +1,190 @@
+// Анализ высоты орбиты: скорость снижения (торможение в атмосфере) и манёвры подъёма
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// Медианная высота за интервал агрегации (сглаживает колебания высоты вдоль витка)
+#[derive(Debug, Clone, Serialize)]
+pub struct AltitudeBucket {
+    /// Среднее время замеров интервала
+    pub at: DateTime<Utc>,
+    /// км
+    pub altitude_km: f64,
+    /// Число замеров в интервале
+    pub samples: i64,
+}
+
+/// Участок между манёврами, на котором высота меняется линейно
+#[derive(Debug, Clone, Serialize)]
+pub struct DecaySegment {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Потеря высоты, м/сут (отрицательное значение - высота растёт)
+    pub decay_rate_m_per_day: f64,
+    pub buckets: usize,
+}
+
+/// Манёвр подъёма орбиты: скачок высоты между соседними интервалами
+#[derive(Debug, Clone, Serialize)]
+pub struct ReboostEvent {
+    /// Последний интервал до манёвра
+    pub from: DateTime<Utc>,
+    /// Первый интервал после манёвра
+    pub to: DateTime<Utc>,
+    pub altitude_before_km: f64,
+    pub altitude_after_km: f64,
+    /// Прирост высоты, м
+    pub magnitude_m: f64,
+}
+
+/// Результат анализа истории высот
+#[derive(Debug, Clone, Serialize)]
+pub struct AltitudeAnalysis {
+    pub norad_id: i32,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub bucket_hours: i64,
+    pub reboost_threshold_m: f64,
+    pub samples: i64,
+    /// Средняя скорость снижения по участкам без манёвров (взвешена по длительности), м/сут
+    pub decay_rate_m_per_day: Option<f64>,
+    pub current_altitude_km: Option<f64>,
+    pub segments: Vec<DecaySegment>,
+    pub reboosts: Vec<ReboostEvent>,
+    pub buckets: Vec<AltitudeBucket>,
+}
+
+/// Разбор ряда на участки снижения и манёвры подъёма.
+/// Скачок вверх больше `threshold_km` между соседними интервалами считается манёвром;
+/// подряд идущие скачки (манёвр, попавший на границу интервалов) объединяются в один.
+pub fn detect(buckets: &[AltitudeBucket], threshold_km: f64) -> (Vec<DecaySegment>, Vec<ReboostEvent>) {
+    let mut reboosts: Vec<ReboostEvent> = Vec::new();
+    let mut segments = Vec::new();
+    let mut segment_start = 0;
+    let mut previous_jump: Option<usize> = None;
+
+    for i in 1..buckets.len() {
+        let (before, after) = (&buckets[i - 1], &buckets[i]);
+        if after.altitude_km - before.altitude_km <= threshold_km {
+            continue;
+        }
+
+        match (previous_jump, reboosts.last_mut()) {
+            (Some(prev), Some(last)) if prev == i - 1 => {
+                last.to = after.at;
+                last.altitude_after_km = after.altitude_km;
+                last.magnitude_m = (last.altitude_after_km - last.altitude_before_km) * 1000.0;
+            }
+            _ => {
+                segments.extend(fit_segment(&buckets[segment_start..i]));
+                reboosts.push(ReboostEvent {
+                    from: before.at,
+                    to: after.at,
+                    altitude_before_km: before.altitude_km,
+                    altitude_after_km: after.altitude_km,
+                    magnitude_m: (after.altitude_km - before.altitude_km) * 1000.0,
+                });
+            }
+        }
+        previous_jump = Some(i);
+        segment_start = i;
+    }
+    segments.extend(fit_segment(&buckets[segment_start..]));
+
+    (segments, reboosts)
+}
+
+/// Средняя скорость снижения, взвешенная по длительности участков
+pub fn weighted_decay_rate(segments: &[DecaySegment]) -> Option<f64> {
+    let (weighted, total) = segments.iter().fold((0.0, 0.0), |(weighted, total), s| {
+        let days = seconds_between(s.from, s.to) / SECONDS_PER_DAY;
+        (weighted + s.decay_rate_m_per_day * days, total + days)
+    });
+    (total > 0.0).then(|| weighted / total)
+}
+
+fn fit_segment(buckets: &[AltitudeBucket]) -> Option<DecaySegment> {
+    let (first, last) = (buckets.first()?, buckets.last()?);
+    let points: Vec<(f64, f64)> = buckets
+        .iter()
+        .map(|b| (seconds_between(first.at, b.at) / SECONDS_PER_DAY, b.altitude_km))
+        .collect();
+    let slope_km_per_day = linear_slope(&points)?;
+
+    Some(DecaySegment {
+        from: first.at,
+        to: last.at,
+        decay_rate_m_per_day: -slope_km_per_day * 1000.0,
+        buckets: buckets.len(),
+    })
+}
+
+/// Наклон прямой МНК по точкам (x, y); None, если точек меньше двух или x не меняется
+pub fn linear_slope(points: &[(f64, f64)]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    if sxx == 0.0 {
+        return None;
+    }
+    Some(sxy / sxx)
+}
+
+fn seconds_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_milliseconds() as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    /// Сутки по 4 интервала, снижение 100 м/сут, подъём на 1.5 км между 5-м и 6-м днём
+    fn series() -> Vec<AltitudeBucket> {
+        let start = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        (0..40)
+            .map(|i| {
+                let days = i as f64 / 4.0;
+                let boost = if i >= 20 { 1.5 } else { 0.0 };
+                AltitudeBucket {
+                    at: start + Duration::hours(6 * i),
+                    altitude_km: 420.0 - 0.1 * days + boost,
+                    samples: 10,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_reboost_and_decay_rate() {
+        let (segments, reboosts) = detect(&series(), 0.3);
+
+        assert_eq!(reboosts.len(), 1);
+        assert!((reboosts[0].magnitude_m - 1475.0).abs() < 1e-6);
+        assert_eq!(segments.len(), 2);
+        for segment in &segments {
+            assert!((segment.decay_rate_m_per_day - 100.0).abs() < 1e-6);
+        }
+        assert!((weighted_decay_rate(&segments).unwrap() - 100.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn merges_reboost_split_across_buckets() {
+        let mut buckets = series();
+        // Половина прироста приходится на предыдущий интервал
+        buckets[19].altitude_km += 0.75;
+
+        let (segments, reboosts) = detect(&buckets, 0.3);
+
+        assert_eq!(reboosts.len(), 1);
+        assert_eq!(reboosts[0].from, buckets[18].at);
+        assert_eq!(reboosts[0].to, buckets[20].at);
+        assert_eq!(segments.len(), 2);
+    }
+}
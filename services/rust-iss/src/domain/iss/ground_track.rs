@@ -0,0 +1,278 @@
+// Наземная трасса: разбиение по антимеридиану и экспорт в GeoJSON / KML
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde_json::{json, Value};
+use super::{IssPosition, IssTrackPoint};
+
+/// Источник точек трассы
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackSource {
+    /// Сохранённые замеры iss_fetch_log
+    Stored,
+    /// Расчёт SGP4 по TLE
+    Propagated,
+}
+
+impl TrackSource {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "stored" => Some(Self::Stored),
+            "propagated" => Some(Self::Propagated),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stored => "stored",
+            Self::Propagated => "propagated",
+        }
+    }
+}
+
+/// Точка трассы: сохранённый замер или расчётная точка
+#[derive(Debug, Clone)]
+pub struct TrackSample {
+    pub at: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// км
+    pub altitude: Option<f64>,
+    /// км/ч
+    pub velocity: Option<f64>,
+}
+
+impl From<&IssTrackPoint> for TrackSample {
+    fn from(p: &IssTrackPoint) -> Self {
+        Self {
+            at: p.at,
+            latitude: p.latitude,
+            longitude: p.longitude,
+            altitude: Some(p.altitude),
+            velocity: Some(p.velocity),
+        }
+    }
+}
+
+impl TrackSample {
+    /// Замер без координат в трассу не попадает
+    pub fn from_position(p: &IssPosition) -> Option<Self> {
+        Some(Self {
+            at: p.position_at.unwrap_or(p.fetched_at),
+            latitude: p.latitude?,
+            longitude: p.longitude?,
+            altitude: p.altitude,
+            velocity: p.velocity,
+        })
+    }
+}
+
+fn lerp(a: f64, b: f64, frac: f64) -> f64 {
+    a + (b - a) * frac
+}
+
+fn lerp_opt(a: Option<f64>, b: Option<f64>, frac: f64) -> Option<f64> {
+    Some(lerp(a?, b?, frac))
+}
+
+/// Точка пересечения антимеридиана между `a` и `b` на долготе `edge` (±180)
+fn crossing(a: &TrackSample, b: &TrackSample, edge: f64) -> TrackSample {
+    // Разворачиваем долготу b так, чтобы отрезок шёл через ±180
+    let b_lon = if edge > 0.0 { b.longitude + 360.0 } else { b.longitude - 360.0 };
+    let frac = (edge - a.longitude) / (b_lon - a.longitude);
+    let dt_ms = (b.at - a.at).num_milliseconds() as f64;
+
+    TrackSample {
+        at: a.at + Duration::milliseconds((dt_ms * frac).round() as i64),
+        latitude: lerp(a.latitude, b.latitude, frac),
+        longitude: edge,
+        altitude: lerp_opt(a.altitude, b.altitude, frac),
+        velocity: lerp_opt(a.velocity, b.velocity, frac),
+    }
+}
+
+/// Разбить трассу на отрезки, не пересекающие антимеридиан.
+/// На стыке добавляются интерполированные точки на +180 / -180,
+/// поэтому каждый отрезок после разрыва содержит минимум две точки.
+pub fn split_antimeridian(samples: &[TrackSample]) -> Vec<Vec<TrackSample>> {
+    let mut segments: Vec<Vec<TrackSample>> = Vec::new();
+    let mut current: Vec<TrackSample> = Vec::new();
+
+    for sample in samples {
+        if let Some(prev) = current.last() {
+            if (sample.longitude - prev.longitude).abs() > 180.0 {
+                let edge = if prev.longitude > 0.0 { 180.0 } else { -180.0 };
+                let mut cross = crossing(prev, sample, edge);
+                current.push(cross.clone());
+                segments.push(std::mem::take(&mut current));
+                cross.longitude = -edge;
+                current.push(cross);
+            }
+        }
+        current.push(sample.clone());
+    }
+
+    if !current.is_empty() {
+        segments.push(current);
+    }
+
+    segments
+}
+
+fn rfc3339(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// FeatureCollection с одной линией трассы.
+/// Свойства точек - в `coordinateProperties` (массивы той же формы, что и координаты).
+pub fn to_geojson(segments: &[Vec<TrackSample>], properties: Value) -> Value {
+    if segments.is_empty() {
+        return json!({ "type": "FeatureCollection", "features": [] });
+    }
+
+    let coords: Vec<Vec<Value>> = segments
+        .iter()
+        .map(|seg| {
+            seg.iter()
+                .map(|p| match p.altitude {
+                    Some(alt) => json!([p.longitude, p.latitude, alt * 1000.0]),
+                    None => json!([p.longitude, p.latitude]),
+                })
+                .collect()
+        })
+        .collect();
+    let column = |f: &dyn Fn(&TrackSample) -> Value| -> Vec<Vec<Value>> {
+        segments.iter().map(|seg| seg.iter().map(f).collect()).collect()
+    };
+    let times = column(&|p| json!(rfc3339(p.at)));
+    let altitudes = column(&|p| json!(p.altitude));
+    let velocities = column(&|p| json!(p.velocity));
+
+    let (geometry, coordinate_properties) = if segments.len() == 1 {
+        (
+            json!({ "type": "LineString", "coordinates": coords[0] }),
+            json!({ "time": times[0], "altitude_km": altitudes[0], "velocity_kmh": velocities[0] }),
+        )
+    } else {
+        (
+            json!({ "type": "MultiLineString", "coordinates": coords }),
+            json!({ "time": times, "altitude_km": altitudes, "velocity_kmh": velocities }),
+        )
+    };
+
+    let mut props = match properties {
+        Value::Object(map) => map,
+        _ => serde_json::Map::new(),
+    };
+    props.insert("coordinateProperties".to_string(), coordinate_properties);
+
+    json!({
+        "type": "FeatureCollection",
+        "features": [{
+            "type": "Feature",
+            "geometry": geometry,
+            "properties": props,
+        }],
+    })
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// KML-документ: gx:MultiTrack, по одному gx:Track на отрезок,
+/// высота и скорость - в ExtendedData как массивы по точкам
+pub fn to_kml(segments: &[Vec<TrackSample>], name: &str) -> String {
+    let mut kml = String::new();
+    kml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    kml.push_str("<kml xmlns=\"http://www.opengis.net/kml/2.2\" xmlns:gx=\"http://www.google.com/kml/ext/2.2\">\n");
+    kml.push_str("<Document>\n");
+    kml.push_str(&format!("  <name>{}</name>\n", xml_escape(name)));
+    kml.push_str("  <Schema id=\"track_schema\">\n");
+    kml.push_str("    <gx:SimpleArrayField name=\"altitude_km\" type=\"float\"><displayName>Altitude, km</displayName></gx:SimpleArrayField>\n");
+    kml.push_str("    <gx:SimpleArrayField name=\"velocity_kmh\" type=\"float\"><displayName>Velocity, km/h</displayName></gx:SimpleArrayField>\n");
+    kml.push_str("  </Schema>\n");
+    kml.push_str("  <Placemark>\n");
+    kml.push_str(&format!("    <name>{}</name>\n", xml_escape(name)));
+    kml.push_str("    <gx:MultiTrack>\n");
+    kml.push_str("      <altitudeMode>absolute</altitudeMode>\n");
+    kml.push_str("      <gx:interpolate>1</gx:interpolate>\n");
+
+    for seg in segments {
+        kml.push_str("      <gx:Track>\n");
+        for p in seg {
+            kml.push_str(&format!("        <when>{}</when>\n", rfc3339(p.at)));
+        }
+        for p in seg {
+            kml.push_str(&format!(
+                "        <gx:coord>{} {} {}</gx:coord>\n",
+                p.longitude,
+                p.latitude,
+                p.altitude.map(|a| a * 1000.0).unwrap_or(0.0)
+            ));
+        }
+        kml.push_str("        <ExtendedData>\n");
+        kml.push_str("          <SchemaData schemaUrl=\"#track_schema\">\n");
+        for (field, values) in [
+            ("altitude_km", seg.iter().map(|p| p.altitude).collect::<Vec<_>>()),
+            ("velocity_kmh", seg.iter().map(|p| p.velocity).collect::<Vec<_>>()),
+        ] {
+            kml.push_str(&format!("            <gx:SimpleArrayData name=\"{}\">\n", field));
+            for v in values {
+                kml.push_str(&format!(
+                    "              <gx:value>{}</gx:value>\n",
+                    v.map(|x| x.to_string()).unwrap_or_default()
+                ));
+            }
+            kml.push_str("            </gx:SimpleArrayData>\n");
+        }
+        kml.push_str("          </SchemaData>\n");
+        kml.push_str("        </ExtendedData>\n");
+        kml.push_str("      </gx:Track>\n");
+    }
+
+    kml.push_str("    </gx:MultiTrack>\n");
+    kml.push_str("  </Placemark>\n");
+    kml.push_str("</Document>\n");
+    kml.push_str("</kml>\n");
+    kml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample(sec: i64, lat: f64, lon: f64) -> TrackSample {
+        TrackSample {
+            at: Utc.timestamp_opt(1_700_000_000 + sec, 0).unwrap(),
+            latitude: lat,
+            longitude: lon,
+            altitude: Some(420.0),
+            velocity: Some(27600.0),
+        }
+    }
+
+    #[test]
+    fn splits_at_antimeridian_with_interpolated_edges() {
+        let track = [sample(0, 10.0, 170.0), sample(60, 14.0, -170.0), sample(120, 18.0, -150.0)];
+        let segments = split_antimeridian(&track);
+
+        assert_eq!(segments.len(), 2);
+        let end = segments[0].last().unwrap();
+        let start = &segments[1][0];
+        assert_eq!(end.longitude, 180.0);
+        assert_eq!(start.longitude, -180.0);
+        assert!((end.latitude - 12.0).abs() < 1e-9);
+        assert_eq!(end.at, start.at);
+        assert_eq!((end.at - track[0].at).num_seconds(), 30);
+    }
+
+    #[test]
+    fn keeps_continuous_track_whole() {
+        let track = [sample(0, 0.0, -10.0), sample(60, 2.0, 5.0), sample(120, 4.0, 20.0)];
+        assert_eq!(split_antimeridian(&track).len(), 1);
+    }
+}
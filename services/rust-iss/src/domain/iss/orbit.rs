@@ -0,0 +1,669 @@
+// Орбитальная механика: разбор TLE и пропагатор SGP4 (околоземные орбиты)
+// Реализация следует Vallado et al., "Revisiting Spacetrack Report #3" (2006),
+// режим 'i' (improved), гравитационная модель WGS-72.
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use std::f64::consts::PI;
+use thiserror::Error;
+
+const TWO_PI: f64 = 2.0 * PI;
+const X2O3: f64 = 2.0 / 3.0;
+
+// WGS-72 (константы, на которых построены элементы TLE)
+const MU: f64 = 398600.8;
+const RADIUS_EARTH_KM: f64 = 6378.135;
+const J2: f64 = 0.001082616;
+const J3: f64 = -0.00000253881;
+const J4: f64 = -0.00000165597;
+
+// WGS-84 для перевода в геодезические координаты
+const WGS84_A: f64 = 6378.137;
+const WGS84_F: f64 = 1.0 / 298.257223563;
+
+const MINUTES_PER_DAY: f64 = 1440.0;
+
+#[derive(Debug, Error)]
+pub enum OrbitError {
+    #[error("Invalid TLE: {0}")]
+    InvalidTle(String),
+    #[error("Deep-space orbits (period >= 225 min) are not supported")]
+    DeepSpace,
+    #[error("Propagation failed: {0}")]
+    Propagation(&'static str),
+}
+
+/// Двухстрочный набор орбитальных элементов (Two-Line Element set)
+#[derive(Debug, Clone)]
+pub struct Tle {
+    pub name: Option<String>,
+    pub norad_id: u32,
+    pub epoch: DateTime<Utc>,
+    /// Коэффициент торможения B*, 1/радиус Земли
+    pub bstar: f64,
+    pub inclination_deg: f64,
+    pub raan_deg: f64,
+    pub eccentricity: f64,
+    pub arg_perigee_deg: f64,
+    pub mean_anomaly_deg: f64,
+    /// Среднее движение, оборотов в сутки
+    pub mean_motion_rev_day: f64,
+    pub line1: String,
+    pub line2: String,
+}
+
+impl Tle {
+    pub fn parse(name: Option<&str>, line1: &str, line2: &str) -> Result<Self, OrbitError> {
+        let line1 = line1.trim_end();
+        let line2 = line2.trim_end();
+
+        if line1.len() < 69 || line2.len() < 69 || !line1.starts_with('1') || !line2.starts_with('2') {
+            return Err(OrbitError::InvalidTle("lines must be 69 characters starting with '1' and '2'".to_string()));
+        }
+        for line in [line1, line2] {
+            if !line.is_ascii() {
+                return Err(OrbitError::InvalidTle("non-ASCII characters".to_string()));
+            }
+            if !checksum_ok(line) {
+                return Err(OrbitError::InvalidTle(format!("checksum mismatch in line {}", &line[..1])));
+            }
+        }
+
+        let norad_id: u32 = field(line1, 2, 7)?;
+        let norad_id_2: u32 = field(line2, 2, 7)?;
+        if norad_id != norad_id_2 {
+            return Err(OrbitError::InvalidTle("catalog numbers of line 1 and 2 differ".to_string()));
+        }
+
+        let epoch_year: i32 = field(line1, 18, 20)?;
+        let epoch_day: f64 = field(line1, 20, 32)?;
+        let year = if epoch_year < 57 { 2000 + epoch_year } else { 1900 + epoch_year };
+        let year_start = Utc
+            .with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+            .single()
+            .ok_or_else(|| OrbitError::InvalidTle("invalid epoch year".to_string()))?;
+        let epoch = year_start + Duration::microseconds(((epoch_day - 1.0) * 86_400_000_000.0).round() as i64);
+
+        Ok(Self {
+            name: name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+            norad_id,
+            epoch,
+            bstar: implied_decimal(&line1[53..61])?,
+            inclination_deg: field(line2, 8, 16)?,
+            raan_deg: field(line2, 17, 25)?,
+            eccentricity: format!("0.{}", line2[26..33].trim())
+                .parse()
+                .map_err(|_| OrbitError::InvalidTle("eccentricity".to_string()))?,
+            arg_perigee_deg: field(line2, 34, 42)?,
+            mean_anomaly_deg: field(line2, 43, 51)?,
+            mean_motion_rev_day: field(line2, 52, 63)?,
+            line1: line1.to_string(),
+            line2: line2.to_string(),
+        })
+    }
+
+    /// Возраст набора элементов относительно момента `at`, в часах
+    pub fn age_hours(&self, at: DateTime<Utc>) -> f64 {
+        (at - self.epoch).num_seconds() as f64 / 3600.0
+    }
+}
+
+fn field<T: std::str::FromStr>(line: &str, from: usize, to: usize) -> Result<T, OrbitError> {
+    line[from..to]
+        .trim()
+        .parse()
+        .map_err(|_| OrbitError::InvalidTle(format!("cannot parse columns {}-{}", from + 1, to)))
+}
+
+/// Поле TLE с подразумеваемой десятичной точкой и порядком: " 28098-4" = 0.28098e-4
+fn implied_decimal(raw: &str) -> Result<f64, OrbitError> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(0.0);
+    }
+    let (sign, rest) = match raw.as_bytes()[0] {
+        b'-' => (-1.0, &raw[1..]),
+        b'+' => (1.0, &raw[1..]),
+        _ => (1.0, raw),
+    };
+    let split = rest
+        .rfind(['-', '+'])
+        .filter(|&i| i > 0)
+        .ok_or_else(|| OrbitError::InvalidTle(format!("bad exponent field '{}'", raw)))?;
+    let mantissa: f64 = format!("0.{}", &rest[..split])
+        .parse()
+        .map_err(|_| OrbitError::InvalidTle(format!("bad mantissa '{}'", raw)))?;
+    let exponent: i32 = rest[split..]
+        .parse()
+        .map_err(|_| OrbitError::InvalidTle(format!("bad exponent '{}'", raw)))?;
+    Ok(sign * mantissa * 10f64.powi(exponent))
+}
+
+/// Контрольная сумма строки TLE: сумма цифр, '-' считается за 1, по модулю 10
+fn checksum_ok(line: &str) -> bool {
+    let expected = match line.as_bytes()[68] {
+        c @ b'0'..=b'9' => (c - b'0') as u32,
+        _ => return false,
+    };
+    let sum: u32 = line[..68]
+        .bytes()
+        .map(|c| match c {
+            b'0'..=b'9' => (c - b'0') as u32,
+            b'-' => 1,
+            _ => 0,
+        })
+        .sum();
+    sum % 10 == expected
+}
+
+/// Состояние в инерциальной системе TEME: положение (км) и скорость (км/с)
+#[derive(Debug, Clone, Copy)]
+pub struct TemeState {
+    pub position: [f64; 3],
+    pub velocity: [f64; 3],
+}
+
+impl TemeState {
+    pub fn speed_km_s(&self) -> f64 {
+        norm(self.velocity)
+    }
+}
+
+/// Геодезические координаты (WGS-84)
+#[derive(Debug, Clone, Copy)]
+pub struct Geodetic {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_km: f64,
+}
+
+/// Пропагатор SGP4, инициализированный одним набором TLE
+#[derive(Debug, Clone)]
+pub struct Sgp4 {
+    epoch: DateTime<Utc>,
+    xke: f64,
+    // Элементы на эпоху (радианы, рад/мин)
+    ecco: f64,
+    inclo: f64,
+    nodeo: f64,
+    argpo: f64,
+    mo: f64,
+    no_unkozai: f64,
+    bstar: f64,
+    // Коэффициенты, вычисленные при инициализации
+    isimp: bool,
+    aycof: f64,
+    con41: f64,
+    cc1: f64,
+    cc4: f64,
+    cc5: f64,
+    d2: f64,
+    d3: f64,
+    d4: f64,
+    delmo: f64,
+    eta: f64,
+    argpdot: f64,
+    omgcof: f64,
+    sinmao: f64,
+    t2cof: f64,
+    t3cof: f64,
+    t4cof: f64,
+    t5cof: f64,
+    x1mth2: f64,
+    x7thm1: f64,
+    mdot: f64,
+    nodedot: f64,
+    xlcof: f64,
+    xmcof: f64,
+    nodecf: f64,
+}
+
+impl Sgp4 {
+    pub fn new(tle: &Tle) -> Result<Self, OrbitError> {
+        let xke = 60.0 / (RADIUS_EARTH_KM.powi(3) / MU).sqrt();
+        let j3oj2 = J3 / J2;
+
+        let ecco = tle.eccentricity;
+        let inclo = tle.inclination_deg.to_radians();
+        let nodeo = tle.raan_deg.to_radians();
+        let argpo = tle.arg_perigee_deg.to_radians();
+        let mo = tle.mean_anomaly_deg.to_radians();
+        let no_kozai = tle.mean_motion_rev_day * TWO_PI / MINUTES_PER_DAY;
+        let bstar = tle.bstar;
+
+        if no_kozai <= 0.0 || !(0.0..1.0).contains(&ecco) {
+            return Err(OrbitError::InvalidTle("mean motion or eccentricity out of range".to_string()));
+        }
+
+        // initl: восстановление «некозаевского» среднего движения
+        let ss = 78.0 / RADIUS_EARTH_KM + 1.0;
+        let qzms2t = ((120.0 - 78.0) / RADIUS_EARTH_KM).powi(4);
+        let eccsq = ecco * ecco;
+        let omeosq = 1.0 - eccsq;
+        let rteosq = omeosq.sqrt();
+        let cosio = inclo.cos();
+        let cosio2 = cosio * cosio;
+
+        let ak = (xke / no_kozai).powf(X2O3);
+        let d1 = 0.75 * J2 * (3.0 * cosio2 - 1.0) / (rteosq * omeosq);
+        let del = d1 / (ak * ak);
+        let adel = ak * (1.0 - del * del - del * (1.0 / 3.0 + 134.0 * del * del / 81.0));
+        let del = d1 / (adel * adel);
+        let no_unkozai = no_kozai / (1.0 + del);
+
+        if TWO_PI / no_unkozai >= 225.0 {
+            return Err(OrbitError::DeepSpace);
+        }
+
+        let ao = (xke / no_unkozai).powf(X2O3);
+        let sinio = inclo.sin();
+        let po = ao * omeosq;
+        let con42 = 1.0 - 5.0 * cosio2;
+        let con41 = -con42 - cosio2 - cosio2;
+        let posq = po * po;
+        let rp = ao * (1.0 - ecco);
+
+        let isimp = rp < 220.0 / RADIUS_EARTH_KM + 1.0;
+
+        let mut sfour = ss;
+        let mut qzms24 = qzms2t;
+        let perige = (rp - 1.0) * RADIUS_EARTH_KM;
+        if perige < 156.0 {
+            sfour = if perige < 98.0 { 20.0 } else { perige - 78.0 };
+            qzms24 = ((120.0 - sfour) / RADIUS_EARTH_KM).powi(4);
+            sfour = sfour / RADIUS_EARTH_KM + 1.0;
+        }
+
+        let pinvsq = 1.0 / posq;
+        let tsi = 1.0 / (ao - sfour);
+        let eta = ao * ecco * tsi;
+        let etasq = eta * eta;
+        let eeta = ecco * eta;
+        let psisq = (1.0 - etasq).abs();
+        let coef = qzms24 * tsi.powi(4);
+        let coef1 = coef / psisq.powf(3.5);
+        let cc2 = coef1
+            * no_unkozai
+            * (ao * (1.0 + 1.5 * etasq + eeta * (4.0 + etasq))
+                + 0.375 * J2 * tsi / psisq * con41 * (8.0 + 3.0 * etasq * (8.0 + etasq)));
+        let cc1 = bstar * cc2;
+        let cc3 = if ecco > 1.0e-4 {
+            -2.0 * coef * tsi * j3oj2 * no_unkozai * sinio / ecco
+        } else {
+            0.0
+        };
+        let x1mth2 = 1.0 - cosio2;
+        let cc4 = 2.0
+            * no_unkozai
+            * coef1
+            * ao
+            * omeosq
+            * (eta * (2.0 + 0.5 * etasq) + ecco * (0.5 + 2.0 * etasq)
+                - J2 * tsi / (ao * psisq)
+                    * (-3.0 * con41 * (1.0 - 2.0 * eeta + etasq * (1.5 - 0.5 * eeta))
+                        + 0.75 * x1mth2 * (2.0 * etasq - eeta * (1.0 + etasq)) * (2.0 * argpo).cos()));
+        let cc5 = 2.0 * coef1 * ao * omeosq * (1.0 + 2.75 * (etasq + eeta) + eeta * etasq);
+
+        let cosio4 = cosio2 * cosio2;
+        let temp1 = 1.5 * J2 * pinvsq * no_unkozai;
+        let temp2 = 0.5 * temp1 * J2 * pinvsq;
+        let temp3 = -0.46875 * J4 * pinvsq * pinvsq * no_unkozai;
+        let mdot = no_unkozai
+            + 0.5 * temp1 * rteosq * con41
+            + 0.0625 * temp2 * rteosq * (13.0 - 78.0 * cosio2 + 137.0 * cosio4);
+        let argpdot = -0.5 * temp1 * con42
+            + 0.0625 * temp2 * (7.0 - 114.0 * cosio2 + 395.0 * cosio4)
+            + temp3 * (3.0 - 36.0 * cosio2 + 49.0 * cosio4);
+        let xhdot1 = -temp1 * cosio;
+        let nodedot = xhdot1 + (0.5 * temp2 * (4.0 - 19.0 * cosio2) + 2.0 * temp3 * (3.0 - 7.0 * cosio2)) * cosio;
+        let omgcof = bstar * cc3 * argpo.cos();
+        let xmcof = if ecco > 1.0e-4 { -X2O3 * coef * bstar / eeta } else { 0.0 };
+        let nodecf = 3.5 * omeosq * xhdot1 * cc1;
+        let t2cof = 1.5 * cc1;
+        let xlcof_den = if (cosio + 1.0).abs() > 1.5e-12 { 1.0 + cosio } else { 1.5e-12 };
+        let xlcof = -0.25 * j3oj2 * sinio * (3.0 + 5.0 * cosio) / xlcof_den;
+        let aycof = -0.5 * j3oj2 * sinio;
+        let delmo = (1.0 + eta * mo.cos()).powi(3);
+        let sinmao = mo.sin();
+        let x7thm1 = 7.0 * cosio2 - 1.0;
+
+        let (mut d2, mut d3, mut d4, mut t3cof, mut t4cof, mut t5cof) = (0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        if !isimp {
+            let cc1sq = cc1 * cc1;
+            d2 = 4.0 * ao * tsi * cc1sq;
+            let temp = d2 * tsi * cc1 / 3.0;
+            d3 = (17.0 * ao + sfour) * temp;
+            d4 = 0.5 * temp * ao * tsi * (221.0 * ao + 31.0 * sfour) * cc1;
+            t3cof = d2 + 2.0 * cc1sq;
+            t4cof = 0.25 * (3.0 * d3 + cc1 * (12.0 * d2 + 10.0 * cc1sq));
+            t5cof = 0.2 * (3.0 * d4 + 12.0 * cc1 * d3 + 6.0 * d2 * d2 + 15.0 * cc1sq * (2.0 * d2 + cc1sq));
+        }
+
+        Ok(Self {
+            epoch: tle.epoch,
+            xke,
+            ecco,
+            inclo,
+            nodeo,
+            argpo,
+            mo,
+            no_unkozai,
+            bstar,
+            isimp,
+            aycof,
+            con41,
+            cc1,
+            cc4,
+            cc5,
+            d2,
+            d3,
+            d4,
+            delmo,
+            eta,
+            argpdot,
+            omgcof,
+            sinmao,
+            t2cof,
+            t3cof,
+            t4cof,
+            t5cof,
+            x1mth2,
+            x7thm1,
+            mdot,
+            nodedot,
+            xlcof,
+            xmcof,
+            nodecf,
+        })
+    }
+
+    pub fn epoch(&self) -> DateTime<Utc> {
+        self.epoch
+    }
+
+    /// Состояние на момент `at`
+    pub fn propagate_at(&self, at: DateTime<Utc>) -> Result<TemeState, OrbitError> {
+        let minutes = (at - self.epoch).num_milliseconds() as f64 / 60_000.0;
+        self.propagate(minutes)
+    }
+
+    /// Состояние через `tsince` минут от эпохи TLE
+    pub fn propagate(&self, tsince: f64) -> Result<TemeState, OrbitError> {
+        let t = tsince;
+        let xmdf = self.mo + self.mdot * t;
+        let argpdf = self.argpo + self.argpdot * t;
+        let nodedf = self.nodeo + self.nodedot * t;
+        let mut argpm = argpdf;
+        let mut mm = xmdf;
+        let t2 = t * t;
+        let mut nodem = nodedf + self.nodecf * t2;
+        let mut tempa = 1.0 - self.cc1 * t;
+        let mut tempe = self.bstar * self.cc4 * t;
+        let mut templ = self.t2cof * t2;
+
+        if !self.isimp {
+            let delomg = self.omgcof * t;
+            let delm = self.xmcof * ((1.0 + self.eta * xmdf.cos()).powi(3) - self.delmo);
+            let temp = delomg + delm;
+            mm = xmdf + temp;
+            argpm = argpdf - temp;
+            let t3 = t2 * t;
+            let t4 = t3 * t;
+            tempa = tempa - self.d2 * t2 - self.d3 * t3 - self.d4 * t4;
+            tempe += self.bstar * self.cc5 * (mm.sin() - self.sinmao);
+            templ += self.t3cof * t3 + t4 * (self.t4cof + t * self.t5cof);
+        }
+
+        let am = (self.xke / self.no_unkozai).powf(X2O3) * tempa * tempa;
+        let nm = self.xke / am.powf(1.5);
+        let mut em = self.ecco - tempe;
+        if !(-0.001..1.0).contains(&em) {
+            return Err(OrbitError::Propagation("eccentricity out of range"));
+        }
+        if em < 1.0e-6 {
+            em = 1.0e-6;
+        }
+
+        mm += self.no_unkozai * templ;
+        let mut xlm = mm + argpm + nodem;
+        nodem %= TWO_PI;
+        argpm %= TWO_PI;
+        xlm %= TWO_PI;
+        mm = (xlm - argpm - nodem) % TWO_PI;
+
+        let sinip = self.inclo.sin();
+        let cosip = self.inclo.cos();
+
+        // Долгопериодические возмущения
+        let axnl = em * argpm.cos();
+        let temp = 1.0 / (am * (1.0 - em * em));
+        let aynl = em * argpm.sin() + temp * self.aycof;
+        let xl = mm + argpm + nodem + temp * self.xlcof * axnl;
+
+        // Уравнение Кеплера
+        let u = (xl - nodem) % TWO_PI;
+        let mut eo1 = u;
+        let mut tem5: f64 = 9999.9;
+        let mut ktr = 1;
+        let (mut sineo1, mut coseo1) = (0.0, 0.0);
+        while tem5.abs() >= 1.0e-12 && ktr <= 10 {
+            sineo1 = eo1.sin();
+            coseo1 = eo1.cos();
+            tem5 = 1.0 - coseo1 * axnl - sineo1 * aynl;
+            tem5 = (u - aynl * coseo1 + axnl * sineo1 - eo1) / tem5;
+            if tem5.abs() >= 0.95 {
+                tem5 = 0.95f64.copysign(tem5);
+            }
+            eo1 += tem5;
+            ktr += 1;
+        }
+
+        // Короткопериодические возмущения
+        let ecose = axnl * coseo1 + aynl * sineo1;
+        let esine = axnl * sineo1 - aynl * coseo1;
+        let el2 = axnl * axnl + aynl * aynl;
+        let pl = am * (1.0 - el2);
+        if pl < 0.0 {
+            return Err(OrbitError::Propagation("semi-latus rectum is negative"));
+        }
+
+        let rl = am * (1.0 - ecose);
+        let rdotl = am.sqrt() * esine / rl;
+        let rvdotl = pl.sqrt() / rl;
+        let betal = (1.0 - el2).sqrt();
+        let temp = esine / (1.0 + betal);
+        let sinu = am / rl * (sineo1 - aynl - axnl * temp);
+        let cosu = am / rl * (coseo1 - axnl + aynl * temp);
+        let mut su = sinu.atan2(cosu);
+        let sin2u = (cosu + cosu) * sinu;
+        let cos2u = 1.0 - 2.0 * sinu * sinu;
+        let temp = 1.0 / pl;
+        let temp1 = 0.5 * J2 * temp;
+        let temp2 = temp1 * temp;
+
+        let mrt = rl * (1.0 - 1.5 * temp2 * betal * self.con41) + 0.5 * temp1 * self.x1mth2 * cos2u;
+        su -= 0.25 * temp2 * self.x7thm1 * sin2u;
+        let xnode = nodem + 1.5 * temp2 * cosip * sin2u;
+        let xinc = self.inclo + 1.5 * temp2 * cosip * sinip * cos2u;
+        let mvt = rdotl - nm * temp1 * self.x1mth2 * sin2u / self.xke;
+        let rvdot = rvdotl + nm * temp1 * (self.x1mth2 * cos2u + 1.5 * self.con41) / self.xke;
+
+        if mrt < 1.0 {
+            return Err(OrbitError::Propagation("satellite has decayed"));
+        }
+
+        let (sinsu, cossu) = su.sin_cos();
+        let (snod, cnod) = xnode.sin_cos();
+        let (sini, cosi) = xinc.sin_cos();
+        let xmx = -snod * cosi;
+        let xmy = cnod * cosi;
+        let ux = xmx * sinsu + cnod * cossu;
+        let uy = xmy * sinsu + snod * cossu;
+        let uz = sini * sinsu;
+        let vx = xmx * cossu - cnod * sinsu;
+        let vy = xmy * cossu - snod * sinsu;
+        let vz = sini * cossu;
+
+        let vkmpersec = RADIUS_EARTH_KM * self.xke / 60.0;
+        Ok(TemeState {
+            position: [
+                mrt * ux * RADIUS_EARTH_KM,
+                mrt * uy * RADIUS_EARTH_KM,
+                mrt * uz * RADIUS_EARTH_KM,
+            ],
+            velocity: [
+                (mvt * ux + rvdot * vx) * vkmpersec,
+                (mvt * uy + rvdot * vy) * vkmpersec,
+                (mvt * uz + rvdot * vz) * vkmpersec,
+            ],
+        })
+    }
+}
+
+/// Юлианская дата (UT1 ≈ UTC)
+pub fn julian_date(at: DateTime<Utc>) -> f64 {
+    at.timestamp_millis() as f64 / 86_400_000.0 + 2_440_587.5
+}
+
+/// Гринвичское среднее звёздное время (IAU-82), радианы
+pub fn gmst(at: DateTime<Utc>) -> f64 {
+    let tut1 = (julian_date(at) - 2_451_545.0) / 36525.0;
+    let seconds = -6.2e-6 * tut1.powi(3)
+        + 0.093104 * tut1 * tut1
+        + (876600.0 * 3600.0 + 8640184.812866) * tut1
+        + 67310.54841;
+    (seconds.to_radians() / 240.0).rem_euclid(TWO_PI)
+}
+
+/// Перевод положения из TEME в земную систему ECEF (без учёта движения полюсов)
+pub fn teme_to_ecef(position: [f64; 3], at: DateTime<Utc>) -> [f64; 3] {
+    let (sin_g, cos_g) = gmst(at).sin_cos();
+    [
+        cos_g * position[0] + sin_g * position[1],
+        -sin_g * position[0] + cos_g * position[1],
+        position[2],
+    ]
+}
+
+/// Геодезические координаты WGS-84 по вектору ECEF (км)
+pub fn ecef_to_geodetic(ecef: [f64; 3]) -> Geodetic {
+    let e2 = WGS84_F * (2.0 - WGS84_F);
+    let [x, y, z] = ecef;
+    let p = (x * x + y * y).sqrt();
+    let longitude = y.atan2(x);
+    let mut latitude = z.atan2(p * (1.0 - e2));
+    let mut altitude_km = 0.0;
+
+    for _ in 0..6 {
+        let (sin_lat, cos_lat) = latitude.sin_cos();
+        let n = WGS84_A / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+        // Форма без деления на cos(lat), устойчивая над полюсами
+        altitude_km = p * cos_lat + (z + e2 * n * sin_lat) * sin_lat - n;
+        latitude = z.atan2(p * (1.0 - e2 * n / (n + altitude_km)));
+    }
+
+    Geodetic {
+        latitude: latitude.to_degrees(),
+        longitude: longitude.to_degrees(),
+        altitude_km,
+    }
+}
+
+/// Вектор ECEF (км) для геодезических координат WGS-84
+pub fn geodetic_to_ecef(geo: &Geodetic) -> [f64; 3] {
+    let e2 = WGS84_F * (2.0 - WGS84_F);
+    let (sin_lat, cos_lat) = geo.latitude.to_radians().sin_cos();
+    let (sin_lon, cos_lon) = geo.longitude.to_radians().sin_cos();
+    let n = WGS84_A / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+    [
+        (n + geo.altitude_km) * cos_lat * cos_lon,
+        (n + geo.altitude_km) * cos_lat * sin_lon,
+        (n * (1.0 - e2) + geo.altitude_km) * sin_lat,
+    ]
+}
+
+/// Топоцентрические координаты объекта для наблюдателя
+#[derive(Debug, Clone, Copy)]
+pub struct LookAngles {
+    /// Азимут от севера по часовой стрелке, градусы
+    pub azimuth: f64,
+    pub elevation: f64,
+    pub range_km: f64,
+}
+
+/// Азимут/угол места объекта `target_ecef` из точки `observer`
+pub fn look_angles(target_ecef: [f64; 3], observer: &Geodetic) -> LookAngles {
+    let obs = geodetic_to_ecef(observer);
+    let d = [target_ecef[0] - obs[0], target_ecef[1] - obs[1], target_ecef[2] - obs[2]];
+    let (sin_lat, cos_lat) = observer.latitude.to_radians().sin_cos();
+    let (sin_lon, cos_lon) = observer.longitude.to_radians().sin_cos();
+
+    let south = sin_lat * cos_lon * d[0] + sin_lat * sin_lon * d[1] - cos_lat * d[2];
+    let east = -sin_lon * d[0] + cos_lon * d[1];
+    let zenith = cos_lat * cos_lon * d[0] + cos_lat * sin_lon * d[1] + sin_lat * d[2];
+    let range_km = norm(d);
+
+    LookAngles {
+        azimuth: east.atan2(-south).to_degrees().rem_euclid(360.0),
+        elevation: (zenith / range_km).asin().to_degrees(),
+        range_km,
+    }
+}
+
+/// Подспутниковая точка на момент `at`
+pub fn subpoint(state: &TemeState, at: DateTime<Utc>) -> Geodetic {
+    ecef_to_geodetic(teme_to_ecef(state.position, at))
+}
+
+pub(crate) fn norm(v: [f64; 3]) -> f64 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Datelike;
+
+    // Тестовый набор 00005 из Vallado et al. (2006), SGP4-VER.TLE
+    const LINE1: &str = "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753";
+    const LINE2: &str = "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667";
+
+    fn assert_close(actual: [f64; 3], expected: [f64; 3], tolerance: f64) {
+        for i in 0..3 {
+            assert!(
+                (actual[i] - expected[i]).abs() < tolerance,
+                "component {}: {} vs {}",
+                i,
+                actual[i],
+                expected[i]
+            );
+        }
+    }
+
+    #[test]
+    fn parses_tle_fields() {
+        let tle = Tle::parse(Some("TEST"), LINE1, LINE2).unwrap();
+        assert_eq!(tle.norad_id, 5);
+        assert!((tle.bstar - 0.28098e-4).abs() < 1e-12);
+        assert!((tle.eccentricity - 0.1859667).abs() < 1e-12);
+        assert_eq!(tle.epoch.year(), 2000);
+    }
+
+    #[test]
+    fn rejects_bad_checksum() {
+        let broken = LINE1.replace("4753", "4754");
+        assert!(Tle::parse(None, &broken, LINE2).is_err());
+    }
+
+    #[test]
+    fn matches_reference_vectors() {
+        let tle = Tle::parse(None, LINE1, LINE2).unwrap();
+        let sgp4 = Sgp4::new(&tle).unwrap();
+
+        let s0 = sgp4.propagate(0.0).unwrap();
+        assert_close(s0.position, [7022.46529266, -1400.08296755, 0.03995155], 1e-3);
+        assert_close(s0.velocity, [1.893841015, 6.405893759, 4.534807250], 1e-6);
+
+        let s360 = sgp4.propagate(360.0).unwrap();
+        assert_close(s360.position, [-7154.03120202, -3783.17682504, -3536.19412294], 1e-3);
+        assert_close(s360.velocity, [4.741887409, -4.151817765, -2.093935425], 1e-6);
+    }
+}
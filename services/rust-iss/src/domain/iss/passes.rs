@@ -0,0 +1,219 @@
+// Поиск пролётов спутника над наблюдателем по пропагатору SGP4
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use super::orbit::{look_angles, teme_to_ecef, Geodetic, LookAngles, OrbitError, Sgp4};
+use crate::domain::solar::{is_sunlit, sun_position_eci};
+
+/// Шаг грубого поиска восхода/захода, с
+const SEARCH_STEP_SEC: f64 = 30.0;
+/// Шаг проверки видимости внутри пролёта, с
+const VISIBILITY_STEP_SEC: f64 = 10.0;
+/// Точность уточнения моментов, с
+const REFINE_TOLERANCE_SEC: f64 = 0.5;
+/// Наблюдатель в темноте, если Солнце ниже -6° (гражданские сумерки)
+const DARKNESS_SUN_ELEVATION: f64 = -6.0;
+/// Пролёт, не закончившийся к концу окна, дотягиваем не дальше этого, с
+const MAX_PASS_SEC: f64 = 1800.0;
+
+/// Точка пролёта: момент и направление на спутник
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassPoint {
+    pub at: DateTime<Utc>,
+    pub azimuth: f64,
+    pub elevation: f64,
+    pub range_km: f64,
+}
+
+/// Один пролёт над горизонтом наблюдателя
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pass {
+    pub rise: PassPoint,
+    pub culmination: PassPoint,
+    pub set: PassPoint,
+    pub duration_sec: f64,
+    pub max_elevation: f64,
+    /// Спутник освещён Солнцем в кульминации
+    pub sunlit: bool,
+    /// Наблюдатель в темноте (Солнце ниже -6°) в кульминации
+    pub observer_in_darkness: bool,
+    /// Есть момент, когда спутник освещён, а наблюдатель в темноте
+    pub visible: bool,
+}
+
+struct PassSearch<'a> {
+    sgp4: &'a Sgp4,
+    observer: &'a Geodetic,
+    start: DateTime<Utc>,
+}
+
+impl PassSearch<'_> {
+    fn at(&self, t: f64) -> DateTime<Utc> {
+        self.start + Duration::milliseconds((t * 1000.0).round() as i64)
+    }
+
+    fn look(&self, t: f64) -> Result<LookAngles, OrbitError> {
+        let at = self.at(t);
+        let state = self.sgp4.propagate_at(at)?;
+        Ok(look_angles(teme_to_ecef(state.position, at), self.observer))
+    }
+
+    fn elevation(&self, t: f64) -> Result<f64, OrbitError> {
+        Ok(self.look(t)?.elevation)
+    }
+
+    fn point(&self, t: f64) -> Result<PassPoint, OrbitError> {
+        let look = self.look(t)?;
+        Ok(PassPoint {
+            at: self.at(t),
+            azimuth: look.azimuth,
+            elevation: look.elevation,
+            range_km: look.range_km,
+        })
+    }
+
+    /// Момент пересечения горизонта между `lo` и `hi` (знаки высоты различны)
+    fn crossing(&self, mut lo: f64, mut hi: f64) -> Result<f64, OrbitError> {
+        let rising = self.elevation(lo)? <= 0.0;
+        while hi - lo > REFINE_TOLERANCE_SEC {
+            let mid = (lo + hi) / 2.0;
+            if (self.elevation(mid)? > 0.0) == rising {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+        Ok((lo + hi) / 2.0)
+    }
+
+    /// Максимум высоты на отрезке (золотое сечение; функция унимодальна в пределах пролёта)
+    fn culmination(&self, mut lo: f64, mut hi: f64) -> Result<f64, OrbitError> {
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        let mut a = hi - ratio * (hi - lo);
+        let mut b = lo + ratio * (hi - lo);
+        let (mut fa, mut fb) = (self.elevation(a)?, self.elevation(b)?);
+        while hi - lo > REFINE_TOLERANCE_SEC {
+            if fa < fb {
+                lo = a;
+                a = b;
+                fa = fb;
+                b = lo + ratio * (hi - lo);
+                fb = self.elevation(b)?;
+            } else {
+                hi = b;
+                b = a;
+                fb = fa;
+                a = hi - ratio * (hi - lo);
+                fa = self.elevation(a)?;
+            }
+        }
+        Ok((lo + hi) / 2.0)
+    }
+
+    fn sunlit(&self, t: f64) -> Result<bool, OrbitError> {
+        let at = self.at(t);
+        Ok(is_sunlit(self.sgp4.propagate_at(at)?.position, at))
+    }
+
+    fn observer_in_darkness(&self, t: f64) -> bool {
+        let at = self.at(t);
+        let sun = look_angles(teme_to_ecef(sun_position_eci(at), at), self.observer);
+        sun.elevation < DARKNESS_SUN_ELEVATION
+    }
+
+    fn build(&self, rise: f64, set: f64) -> Result<Pass, OrbitError> {
+        let peak = self.culmination(rise, set)?;
+        let culmination = self.point(peak)?;
+
+        let mut visible = false;
+        let mut t = rise;
+        while t <= set && !visible {
+            visible = self.sunlit(t)? && self.observer_in_darkness(t);
+            t += VISIBILITY_STEP_SEC;
+        }
+
+        Ok(Pass {
+            rise: self.point(rise)?,
+            set: self.point(set)?,
+            duration_sec: set - rise,
+            max_elevation: culmination.elevation,
+            sunlit: self.sunlit(peak)?,
+            observer_in_darkness: self.observer_in_darkness(peak),
+            visible,
+            culmination,
+        })
+    }
+}
+
+/// Пролёты, начинающиеся в окне [from, to]. Пролёт, идущий в момент `from`, пропускается.
+pub fn find_passes(
+    sgp4: &Sgp4,
+    observer: &Geodetic,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<Pass>, OrbitError> {
+    let search = PassSearch { sgp4, observer, start: from };
+    let end = (to - from).num_milliseconds() as f64 / 1000.0;
+
+    let mut passes = Vec::new();
+    let mut t = 0.0;
+    let mut prev = search.elevation(t)?;
+    let mut rise: Option<f64> = None;
+
+    loop {
+        let next = t + SEARCH_STEP_SEC;
+        // После конца окна ищем только заход уже начавшегося пролёта
+        if next > end && (rise.is_none() || next > end + MAX_PASS_SEC) {
+            break;
+        }
+        let elevation = search.elevation(next)?;
+
+        if prev <= 0.0 && elevation > 0.0 {
+            rise = Some(search.crossing(t, next)?);
+        } else if prev > 0.0 && elevation <= 0.0 {
+            if let Some(r) = rise.take() {
+                let set = search.crossing(t, next)?;
+                passes.push(search.build(r, set)?);
+            }
+        }
+
+        prev = elevation;
+        t = next;
+    }
+
+    Ok(passes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::iss::orbit::Tle;
+    use chrono::TimeZone;
+
+    // МКС, эпоха 2008-09-20
+    const LINE1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+    const LINE2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+    #[test]
+    fn passes_are_ordered_and_disjoint() {
+        let tle = Tle::parse(Some("ISS (ZARYA)"), LINE1, LINE2).unwrap();
+        let sgp4 = Sgp4::new(&tle).unwrap();
+        let observer = Geodetic { latitude: 55.75, longitude: 37.62, altitude_km: 0.15 };
+        let from = Utc.with_ymd_and_hms(2008, 9, 20, 12, 0, 0).unwrap();
+        let to = from + Duration::hours(24);
+
+        let passes = find_passes(&sgp4, &observer, from, to).unwrap();
+        assert!(!passes.is_empty());
+
+        for pass in &passes {
+            assert!(pass.rise.at < pass.culmination.at, "{:?}", pass);
+            assert!(pass.culmination.at < pass.set.at, "{:?}", pass);
+            assert!(pass.rise.at >= from && pass.rise.at <= to);
+            assert!(pass.max_elevation > 0.0);
+            assert!(pass.max_elevation >= pass.rise.elevation && pass.max_elevation >= pass.set.elevation);
+            assert!(pass.duration_sec > 0.0 && pass.duration_sec < MAX_PASS_SEC);
+        }
+        for pair in passes.windows(2) {
+            assert!(pair[0].set.at < pair[1].rise.at);
+        }
+    }
+}
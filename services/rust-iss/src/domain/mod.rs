@@ -2,6 +2,11 @@
 pub mod iss;
 pub mod osdr;
 pub mod space_cache;
+pub mod solar;
 pub mod error;
+pub mod geofence;
+pub mod region;
+pub mod satellite;
+pub mod sync_run;
 
 pub use error::*;
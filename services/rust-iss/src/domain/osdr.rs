@@ -1,6 +1,7 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct OsdrItem {
@@ -13,8 +14,1401 @@ pub struct OsdrItem {
     pub raw: Value,
 }
 
+/// Страница списка OSDR (GET /osdr/list)
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OsdrListResponse {
     pub items: Vec<OsdrItem>,
-    pub total: usize,
+    /// Число записей, подходящих под фильтр (по всем страницам)
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
+}
+
+/// Фильтр списка OSDR
+#[derive(Debug, Clone, Default)]
+pub struct OsdrFilter {
+    /// Статус без учёта регистра
+    pub status: Option<String>,
+    pub updated_since: Option<DateTime<Utc>>,
+}
+
+/// Поле сортировки списка OSDR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsdrSort {
+    InsertedAt,
+    UpdatedAt,
+    Title,
+    DatasetId,
+}
+
+impl OsdrSort {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "inserted_at" => Some(Self::InsertedAt),
+            "updated_at" => Some(Self::UpdatedAt),
+            "title" => Some(Self::Title),
+            "dataset_id" => Some(Self::DatasetId),
+            _ => None,
+        }
+    }
+
+    /// Колонка для ORDER BY (только из этого списка - без подстановки пользовательского ввода)
+    pub fn column(&self) -> &'static str {
+        match self {
+            Self::InsertedAt => "inserted_at",
+            Self::UpdatedAt => "updated_at",
+            Self::Title => "title",
+            Self::DatasetId => "dataset_id",
+        }
+    }
+}
+
+/// Направление сортировки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "asc" => Some(Self::Asc),
+            "desc" => Some(Self::Desc),
+            _ => None,
+        }
+    }
+
+    pub fn sql(&self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+}
+
+/// Поля для поиска и фасетов, извлечённые из raw (ключи NASA встречаются в разном написании,
+/// на верхнем уровне и внутри "metadata")
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OsdrMetadata {
+    pub description: Option<String>,
+    pub organisms: Vec<String>,
+    pub assay_types: Vec<String>,
+    pub factors: Vec<String>,
+    /// Название миссии (для статистики)
+    pub mission: Option<String>,
+}
+
+const DESCRIPTION_KEYS: &[&str] = &["description", "study description", "summary", "abstract"];
+const ORGANISM_KEYS: &[&str] = &["organism", "organisms", "study organism", "characteristics organism"];
+const ASSAY_TYPE_KEYS: &[&str] = &[
+    "assay type",
+    "assay types",
+    "study assay technology type",
+    "study assay measurement type",
+];
+const FACTOR_KEYS: &[&str] = &["factors", "factor", "study factor name", "study factor type"];
+
+impl OsdrMetadata {
+    pub fn from_raw(raw: &Value) -> Self {
+        Self {
+            description: lookup_strings(raw, DESCRIPTION_KEYS).into_iter().next(),
+            organisms: lookup_strings(raw, ORGANISM_KEYS),
+            assay_types: lookup_strings(raw, ASSAY_TYPE_KEYS),
+            factors: lookup_strings(raw, FACTOR_KEYS),
+            mission: extract_mission(raw).and_then(|m| m.name),
+        }
+    }
+}
+
+/// Ключ в виде для сравнения: нижний регистр, '_' и '-' как пробел
+fn normalize_key(key: &str) -> String {
+    key.trim()
+        .chars()
+        .map(|c| if c == '_' || c == '-' { ' ' } else { c.to_ascii_lowercase() })
+        .collect()
+}
+
+/// Значения по первому найденному ключу из `keys` (raw, затем raw.metadata), без повторов
+pub(crate) fn lookup_strings(raw: &Value, keys: &[&str]) -> Vec<String> {
+    let scopes = [Some(raw), raw.get("metadata")];
+    for key in keys {
+        for scope in scopes.iter().flatten() {
+            let Some(obj) = scope.as_object() else { continue };
+            let found = obj.iter().find(|(k, _)| normalize_key(k) == *key);
+            if let Some((_, value)) = found {
+                let mut out = Vec::new();
+                collect_strings(value, &mut out);
+                if !out.is_empty() {
+                    return out;
+                }
+            }
+        }
+    }
+    Vec::new()
+}
+
+/// Строки из значения: строка, число, массив или объекты вида {"name"|"value"|"term": ...}
+fn collect_strings(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(s) => {
+            let s = s.trim();
+            if !s.is_empty() && !out.iter().any(|o| o == s) {
+                out.push(s.to_string());
+            }
+        }
+        Value::Number(n) => collect_strings(&Value::String(n.to_string()), out),
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, out)),
+        Value::Object(obj) => {
+            if let Some(v) = ["name", "value", "term"].iter().find_map(|k| obj.get(*k)) {
+                collect_strings(v, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Результат полнотекстового поиска OSDR
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OsdrSearchHit {
+    pub id: i64,
+    pub dataset_id: Option<String>,
+    pub title: Option<String>,
+    pub status: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub rank: f32,
+    /// Заголовок с подсветкой совпадений (<mark>)
+    pub title_highlight: Option<String>,
+    /// Фрагменты описания с подсветкой совпадений (<mark>)
+    pub snippet: Option<String>,
+    pub organisms: Vec<String>,
+    pub assay_types: Vec<String>,
+    pub factors: Vec<String>,
+}
+
+/// Значение фасета и число подходящих датасетов
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacetValue {
+    pub value: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OsdrFacets {
+    pub organism: Vec<FacetValue>,
+    pub assay_type: Vec<FacetValue>,
+    pub factor: Vec<FacetValue>,
+}
+
+/// Датасет на границе периода обновлений (GET /osdr/stats)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsdrStatsDataset {
+    pub dataset_id: Option<String>,
+    pub title: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Число датасетов, обновлённых за месяц ("YYYY-MM", UTC)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsdrMonthCount {
+    pub month: String,
+    pub count: i64,
+}
+
+/// Сводная статистика каталога (GET /osdr/stats)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OsdrStats {
+    pub total: i64,
+    /// Статус; записи без статуса - "unknown"
+    pub by_status: Vec<FacetValue>,
+    pub by_organism: Vec<FacetValue>,
+    pub by_assay_type: Vec<FacetValue>,
+    pub by_mission: Vec<FacetValue>,
+    /// Последние месяцы по updated_at, по возрастанию
+    pub by_month: Vec<OsdrMonthCount>,
+    pub newest: Option<OsdrStatsDataset>,
+    pub oldest: Option<OsdrStatsDataset>,
+}
+
+/// Формат выгрузки /osdr/export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsdrExportFormat {
+    Csv,
+    Xlsx,
+    Jsonl,
+}
+
+impl OsdrExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "csv" => Some(Self::Csv),
+            "xlsx" => Some(Self::Xlsx),
+            "jsonl" => Some(Self::Jsonl),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Xlsx => "xlsx",
+            Self::Jsonl => "jsonl",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            Self::Jsonl => "application/x-ndjson",
+        }
+    }
+}
+
+/// Колонки выгрузки, в порядке OsdrExportRow::text_fields
+pub const OSDR_EXPORT_COLUMNS: &[&str] = &[
+    "id",
+    "dataset_id",
+    "title",
+    "status",
+    "mission",
+    "organisms",
+    "assay_types",
+    "factors",
+    "updated_at",
+    "inserted_at",
+    "description",
+];
+
+/// Строка выгрузки: нормализованные поля без исходного документа
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OsdrExportRow {
+    pub id: i64,
+    pub dataset_id: Option<String>,
+    pub title: Option<String>,
+    pub status: Option<String>,
+    pub mission: Option<String>,
+    pub organisms: Vec<String>,
+    pub assay_types: Vec<String>,
+    pub factors: Vec<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub inserted_at: DateTime<Utc>,
+    pub description: Option<String>,
+}
+
+impl OsdrExportRow {
+    /// Поля текстом (CSV): даты в RFC 3339, списки через "; ", отсутствующее значение - пустая строка
+    pub fn text_fields(&self) -> Vec<String> {
+        let text = |v: &Option<String>| v.clone().unwrap_or_default();
+        vec![
+            self.id.to_string(),
+            text(&self.dataset_id),
+            text(&self.title),
+            text(&self.status),
+            text(&self.mission),
+            self.organisms.join("; "),
+            self.assay_types.join("; "),
+            self.factors.join("; "),
+            self.updated_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            self.inserted_at.to_rfc3339(),
+            text(&self.description),
+        ]
+    }
+}
+
+/// Фильтры по фасетам (точное совпадение значения)
+#[derive(Debug, Clone, Default)]
+pub struct OsdrFacetFilter {
+    pub organism: Option<String>,
+    pub assay_type: Option<String>,
+    pub factor: Option<String>,
+}
+
+/// Ответ GET /osdr/search
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OsdrSearchResponse {
+    pub query: String,
+    pub items: Vec<OsdrSearchHit>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
+    pub facets: OsdrFacets,
+}
+
+/// Датасет OSDR с нормализованными метаданными (GET /osdr/:dataset_id)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OsdrDataset {
+    pub id: i64,
+    pub dataset_id: Option<String>,
+    pub title: Option<String>,
+    pub status: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub inserted_at: DateTime<Utc>,
+    pub description: Option<String>,
+    pub organisms: Vec<String>,
+    pub factors: Vec<String>,
+    pub assays: Vec<OsdrAssay>,
+    pub mission: Option<OsdrMission>,
+    pub files: Vec<OsdrFileRef>,
+    pub links: Vec<OsdrLink>,
+    /// Исходный документ NASA
+    pub raw: Value,
+}
+
+/// Тип исследования (assay): что измеряли и какой технологией
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OsdrAssay {
+    pub measurement_type: Option<String>,
+    pub technology_type: Option<String>,
+    pub platform: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OsdrMission {
+    pub name: Option<String>,
+    pub flight_program: Option<String>,
+    pub space_program: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+/// Файл датасета, упомянутый в документе
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OsdrFileRef {
+    pub name: String,
+    pub url: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OsdrLink {
+    /// api, osdr, doi, publication
+    pub rel: String,
+    pub url: String,
+}
+
+/// Страница исследования на сайте OSDR
+const OSDR_STUDY_URL: &str = "https://osdr.nasa.gov/bio/repo/data/studies/";
+
+impl OsdrDataset {
+    pub fn from_item(item: OsdrItem) -> Self {
+        let raw = &item.raw;
+        let metadata = OsdrMetadata::from_raw(raw);
+
+        Self {
+            description: metadata.description,
+            organisms: metadata.organisms,
+            factors: metadata.factors,
+            assays: extract_assays(raw),
+            mission: extract_mission(raw),
+            files: extract_files(raw),
+            links: extract_links(raw, item.dataset_id.as_deref()),
+            id: item.id,
+            dataset_id: item.dataset_id,
+            title: item.title,
+            status: item.status,
+            updated_at: item.updated_at,
+            inserted_at: item.inserted_at,
+            raw: item.raw,
+        }
+    }
+}
+
+/// Первая строка по ключам `keys` (см. lookup_strings)
+fn lookup_first(raw: &Value, keys: &[&str]) -> Option<String> {
+    lookup_strings(raw, keys).into_iter().next()
+}
+
+/// Целое по первому найденному ключу `keys` (число или строка с числом)
+fn lookup_i64(value: &Value, keys: &[&str]) -> Option<i64> {
+    let obj = value.as_object()?;
+    keys.iter()
+        .find_map(|key| obj.iter().find(|(k, _)| normalize_key(k) == *key).map(|(_, v)| v))
+        .and_then(|v| v.as_i64().or_else(|| v.as_str().and_then(|s| s.trim().parse().ok())))
+}
+
+/// Assays: массив объектов "assays" или параллельные списки study assay * type
+fn extract_assays(raw: &Value) -> Vec<OsdrAssay> {
+    let from_objects: Vec<OsdrAssay> = ["assays", "assay"]
+        .iter()
+        .find_map(|k| raw.get(*k).or_else(|| raw.get("metadata").and_then(|m| m.get(*k))))
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter(|item| item.is_object())
+                .map(|item| OsdrAssay {
+                    measurement_type: lookup_first(item, &["measurement type", "measurement", "study assay measurement type"]),
+                    technology_type: lookup_first(item, &["technology type", "technology", "study assay technology type"]),
+                    platform: lookup_first(item, &["platform", "technology platform", "study assay technology platform"]),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if !from_objects.is_empty() {
+        return from_objects;
+    }
+
+    let measurements = lookup_strings(raw, &["study assay measurement type"]);
+    let technologies = lookup_strings(raw, &["study assay technology type", "assay type", "assay types"]);
+    let platforms = lookup_strings(raw, &["study assay technology platform"]);
+    let count = measurements.len().max(technologies.len()).max(platforms.len());
+
+    (0..count)
+        .map(|i| OsdrAssay {
+            measurement_type: measurements.get(i).cloned(),
+            technology_type: technologies.get(i).cloned(),
+            platform: platforms.get(i).cloned(),
+        })
+        .collect()
+}
+
+fn extract_mission(raw: &Value) -> Option<OsdrMission> {
+    // "mission" бывает и строкой-названием, и объектом
+    let nested = raw
+        .get("mission")
+        .or_else(|| raw.get("metadata").and_then(|m| m.get("mission")))
+        .filter(|v| v.is_object());
+    let pick = |keys: &[&str]| nested.and_then(|n| lookup_first(n, keys)).or_else(|| lookup_first(raw, keys));
+
+    let mission = OsdrMission {
+        name: pick(&["mission name", "name", "mission", "project title"]),
+        flight_program: pick(&["flight program", "project identifier"]),
+        space_program: pick(&["space program", "managing nasa center"]),
+        start_date: pick(&["mission start date", "start date", "start"]),
+        end_date: pick(&["mission end date", "end date", "end"]),
+    };
+
+    let empty = mission.name.is_none()
+        && mission.flight_program.is_none()
+        && mission.space_program.is_none()
+        && mission.start_date.is_none()
+        && mission.end_date.is_none();
+    (!empty).then_some(mission)
+}
+
+fn extract_files(raw: &Value) -> Vec<OsdrFileRef> {
+    ["files", "study files", "data files"]
+        .iter()
+        .find_map(|k| raw.get(*k).or_else(|| raw.get("metadata").and_then(|m| m.get(*k))))
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    Some(OsdrFileRef {
+                        name: lookup_first(item, &["file name", "file", "name"])?,
+                        url: lookup_first(item, &["remote url", "url", "download url", "link"]),
+                        size_bytes: lookup_i64(item, &["file size", "size"]),
+                        category: lookup_first(item, &["category", "type", "data type"]),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn extract_links(raw: &Value, dataset_id: Option<&str>) -> Vec<OsdrLink> {
+    let mut links = Vec::new();
+    let mut push = |rel: &str, url: String| {
+        if !links.iter().any(|l: &OsdrLink| l.url == url) {
+            links.push(OsdrLink { rel: rel.to_string(), url });
+        }
+    };
+
+    if let Some(url) = lookup_first(raw, &["rest url", "rest_url", "api url"]) {
+        push("api", url);
+    }
+    if let Some(id) = dataset_id.filter(|id| id.starts_with("OSD-")) {
+        push("osdr", format!("{}{}", OSDR_STUDY_URL, id));
+    }
+    for doi in lookup_strings(raw, &["doi", "study doi", "dataset doi"]) {
+        push("doi", doi_url(&doi));
+    }
+    for doi in lookup_strings(raw, &["study publication doi", "publication doi"]) {
+        push("publication", doi_url(&doi));
+    }
+    links
+}
+
+fn doi_url(doi: &str) -> String {
+    if doi.starts_with("http://") || doi.starts_with("https://") {
+        doi.to_string()
+    } else {
+        format!("https://doi.org/{}", doi.trim_start_matches("doi:"))
+    }
+}
+
+/// Позиция постраничного обхода каталога: URL страницы и её номер (с единицы)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OsdrCursor {
+    pub url: String,
+    pub page: u32,
+}
+
+/// Страница каталога: записи и ссылка на следующую страницу
+#[derive(Debug, Clone)]
+pub struct OsdrCataloguePage {
+    pub items: Vec<Value>,
+    pub next_url: Option<String>,
+}
+
+/// Состояние постраничного обхода: страница, которую загрузить следующей, и остаток лимита
+#[derive(Debug, Clone)]
+pub struct OsdrWalk {
+    next: Option<OsdrCursor>,
+    pages_left: u32,
+}
+
+impl OsdrWalk {
+    pub fn new(start: OsdrCursor, max_pages: u32) -> Self {
+        Self { next: Some(start), pages_left: max_pages }
+    }
+
+    /// Страница для загрузки; None - каталог пройден или исчерпан лимит страниц
+    pub fn pending(&self) -> Option<&OsdrCursor> {
+        self.next.as_ref().filter(|_| self.pages_left > 0)
+    }
+
+    /// Учесть загруженную страницу `pending()`. Пока страница не загружена (ошибка),
+    /// advance не вызывается и позиция остаётся на ней.
+    pub fn advance(&mut self, page: &OsdrCataloguePage) {
+        let Some(cursor) = self.pending() else {
+            return;
+        };
+        // Ссылка на ту же страницу - конец каталога, а не бесконечный цикл
+        let next = page
+            .next_url
+            .clone()
+            .filter(|url| *url != cursor.url)
+            .map(|url| OsdrCursor { url, page: cursor.page + 1 });
+        self.next = next;
+        self.pages_left -= 1;
+    }
+
+    /// Страница, с которой продолжить обход; None - каталог пройден до конца
+    pub fn resume_cursor(&self) -> Option<&OsdrCursor> {
+        self.next.as_ref()
+    }
+}
+
+/// Ключи со списком записей в постраничных ответах
+const PAGE_ITEM_KEYS: &[&str] = &["results", "items", "data", "datasets"];
+
+/// Разбор страницы каталога. Записи: массив под results/items/data/datasets, объект
+/// { "OSD-1": {...}, ... } (ключ добавляется как dataset_id) или массив верхнего уровня.
+/// Следующая страница: ссылка next (links.next, _links.next.href, next_page) или
+/// page/total_pages - тогда номер подставляется в параметр page текущего URL.
+pub fn parse_catalogue_page(json: Value, url: &str) -> OsdrCataloguePage {
+    let next_url = next_page_url(&json, url);
+
+    let items = match json {
+        Value::Array(items) => items,
+        Value::Object(mut obj) => {
+            match PAGE_ITEM_KEYS.iter().find(|k| obj.get(**k).is_some_and(|v| v.is_array())) {
+                Some(key) => match obj.remove(*key) {
+                    Some(Value::Array(items)) => items,
+                    _ => Vec::new(),
+                },
+                // Конвертируем объект { "OSD-1": {...}, "OSD-100": {...} }
+                // в массив объектов с добавлением поля dataset_id
+                None if obj.values().any(|v| v.is_object()) => obj
+                    .into_iter()
+                    .filter_map(|(key, mut item)| {
+                        item.as_object_mut()?
+                            .insert("dataset_id".to_string(), Value::String(key));
+                        Some(item)
+                    })
+                    .collect(),
+                None => vec![Value::Object(obj)],
+            }
+        }
+        other => vec![other],
+    };
+
+    OsdrCataloguePage { items, next_url }
+}
+
+fn next_page_url(json: &Value, url: &str) -> Option<String> {
+    let link = [
+        json.get("next"),
+        json.get("next_page").filter(|v| v.is_string()),
+        json.get("nextPage").filter(|v| v.is_string()),
+        json.pointer("/links/next"),
+        json.pointer("/links/next/href"),
+        json.pointer("/_links/next/href"),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(|v| v.as_str().map(str::trim).filter(|s| !s.is_empty()));
+    if let Some(link) = link {
+        return Some(if link.starts_with("http://") || link.starts_with("https://") {
+            link.to_string()
+        } else if link.starts_with('?') {
+            format!("{}{}", url.split('?').next().unwrap_or(url), link)
+        } else {
+            absolute_url(url_origin(url), link)
+        });
+    }
+
+    let number = |keys: &[&str]| keys.iter().find_map(|k| json.get(*k).and_then(|v| v.as_u64()));
+    let page = number(&["page", "current_page", "currentPage"])?;
+    let total = number(&["total_pages", "totalPages", "pages", "last_page"])?;
+    (page < total).then(|| with_query_param(url, "page", &(page + 1).to_string()))
+}
+
+/// "scheme://host[:port]" из URL
+pub fn url_origin(url: &str) -> &str {
+    let host_start = url.find("://").map_or(0, |i| i + 3);
+    match url[host_start..].find('/') {
+        Some(i) => &url[..host_start + i],
+        None => url,
+    }
+}
+
+/// URL с параметром `key=value` (заменяет прежнее значение)
+fn with_query_param(url: &str, key: &str, value: &str) -> String {
+    let (base, query) = url.split_once('?').unwrap_or((url, ""));
+    let mut params: Vec<String> = query
+        .split('&')
+        .filter(|p| !p.is_empty() && p.split('=').next() != Some(key))
+        .map(str::to_string)
+        .collect();
+    params.push(format!("{}={}", key, value));
+    format!("{}?{}", base, params.join("&"))
+}
+
+/// Страница каталога, которую не удалось загрузить
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsdrPageError {
+    pub page: u32,
+    pub code: String,
+    pub message: String,
+}
+
+/// Запись каталога, подготовленная к сохранению
+#[derive(Debug, Clone)]
+pub struct OsdrRecord {
+    pub dataset_id: String,
+    pub title: Option<String>,
+    pub status: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub metadata: OsdrMetadata,
+    pub raw: Value,
+    /// Хеш содержимого raw: совпадение - запись не изменилась
+    pub content_hash: String,
+}
+
+/// Версия правил нормализации. Записи, сохранённые прежней версией, перезаписываются
+/// при следующей синхронизации, даже если документ NASA не изменился.
+/// Версия 2: название миссии в osdr_items.mission.
+pub const OSDR_SCHEMA_VERSION: i32 = 2;
+
+// Поля документа NASA для полей записи, в порядке приоритета: берётся первое не-null
+const DATASET_ID_FIELDS: &[&str] = &["dataset_id", "id", "accession", "studyId", "osdr_id", "uuid"];
+const TITLE_FIELDS: &[&str] = &["title", "name", "label"];
+const STATUS_FIELDS: &[&str] = &["status", "state", "lifecycle"];
+const UPDATED_FIELDS: &[&str] = &["updated", "updated_at", "modified", "lastUpdated", "timestamp"];
+
+/// Допустимый диапазон числовых дат (секунды Unix): 1970-01-01 .. 2100-01-01.
+/// Миллисекунды и прочие числа вне диапазона - ошибка, а не текущее время.
+const MIN_EPOCH_SECONDS: i64 = 0;
+const MAX_EPOCH_SECONDS: i64 = 4_102_444_800;
+
+/// Ошибка нормализации поля: field - ключ исходного документа
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OsdrFieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Документ каталога, не прошедший нормализацию
+#[derive(Debug, Clone)]
+pub struct OsdrRejected {
+    /// dataset_id, если его удалось прочитать
+    pub dataset_id: Option<String>,
+    pub errors: Vec<OsdrFieldError>,
+    pub raw: Value,
+    pub content_hash: String,
+}
+
+/// Хеш содержимого документа (UUID v5; to_string даёт ключи объектов в отсортированном порядке)
+pub fn content_hash(raw: &Value) -> String {
+    Uuid::new_v5(&Uuid::NAMESPACE_OID, raw.to_string().as_bytes()).simple().to_string()
+}
+
+impl OsdrRecord {
+    /// Нормализация документа каталога по OSDR_SCHEMA_VERSION. Все ошибки полей собираются сразу;
+    /// документ без dataset_id или с полем неверного типа отклоняется целиком.
+    pub fn normalize(raw: Value) -> Result<Self, OsdrRejected> {
+        let content_hash = content_hash(&raw);
+        if !raw.is_object() {
+            return Err(OsdrRejected {
+                dataset_id: None,
+                errors: vec![field_error("$", format!("expected object, got {}", type_name(&raw)))],
+                raw,
+                content_hash,
+            });
+        }
+
+        let mut errors = Vec::new();
+        let dataset_id = collect_error(&mut errors, normalize_dataset_id(&raw));
+        let title = collect_error(&mut errors, normalize_text(&raw, TITLE_FIELDS));
+        let status = collect_error(&mut errors, normalize_text(&raw, STATUS_FIELDS));
+        let updated_at = collect_error(&mut errors, normalize_timestamp(&raw, UPDATED_FIELDS));
+        if dataset_id.is_none() && !errors.iter().any(|e| DATASET_ID_FIELDS.contains(&e.field.as_str())) {
+            errors.push(field_error(
+                "dataset_id",
+                format!("missing, expected one of: {}", DATASET_ID_FIELDS.join(", ")),
+            ));
+        }
+
+        match dataset_id {
+            Some(dataset_id) if errors.is_empty() => Ok(Self {
+                dataset_id,
+                title,
+                status,
+                updated_at,
+                metadata: OsdrMetadata::from_raw(&raw),
+                raw,
+                content_hash,
+            }),
+            dataset_id => Err(OsdrRejected { dataset_id, errors, raw, content_hash }),
+        }
+    }
+}
+
+fn collect_error<T>(errors: &mut Vec<OsdrFieldError>, result: Result<Option<T>, OsdrFieldError>) -> Option<T> {
+    result.unwrap_or_else(|e| {
+        errors.push(e);
+        None
+    })
+}
+
+fn field_error(field: &str, message: impl Into<String>) -> OsdrFieldError {
+    OsdrFieldError {
+        field: field.to_string(),
+        message: message.into(),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Первое не-null поле из списка: (ключ, значение)
+fn mapped_field<'a>(raw: &'a Value, fields: &[&'static str]) -> Option<(&'static str, &'a Value)> {
+    fields
+        .iter()
+        .find_map(|k| raw.get(*k).filter(|v| !v.is_null()).map(|v| (*k, v)))
+}
+
+/// Идентификатор: непустая строка или целое число
+fn normalize_dataset_id(raw: &Value) -> Result<Option<String>, OsdrFieldError> {
+    let Some((field, value)) = mapped_field(raw, DATASET_ID_FIELDS) else {
+        return Ok(None);
+    };
+    match value {
+        Value::String(s) if !s.trim().is_empty() => Ok(Some(s.trim().to_string())),
+        Value::String(_) => Err(field_error(field, "must not be empty")),
+        Value::Number(n) if n.is_i64() || n.is_u64() => Ok(Some(n.to_string())),
+        other => Err(field_error(field, format!("expected string or integer, got {}", type_name(other)))),
+    }
+}
+
+/// Текстовое поле: строка; пустая строка - значения нет
+fn normalize_text(raw: &Value, fields: &[&'static str]) -> Result<Option<String>, OsdrFieldError> {
+    let Some((field, value)) = mapped_field(raw, fields) else {
+        return Ok(None);
+    };
+    match value {
+        Value::String(s) => Ok(Some(s.trim().to_string()).filter(|s| !s.is_empty())),
+        other => Err(field_error(field, format!("expected string, got {}", type_name(other)))),
+    }
+}
+
+/// Дата: RFC 3339, "YYYY-MM-DD HH:MM:SS" (UTC), "YYYY-MM-DD" или целые секунды Unix
+fn normalize_timestamp(raw: &Value, fields: &[&'static str]) -> Result<Option<DateTime<Utc>>, OsdrFieldError> {
+    let Some((field, value)) = mapped_field(raw, fields) else {
+        return Ok(None);
+    };
+    match value {
+        Value::String(s) => {
+            let s = s.trim();
+            if s.is_empty() {
+                return Ok(None);
+            }
+            DateTime::parse_from_rfc3339(s)
+                .map(|dt| dt.with_timezone(&Utc))
+                .ok()
+                .or_else(|| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").ok().map(|dt| dt.and_utc()))
+                .or_else(|| {
+                    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                        .ok()
+                        .and_then(|d| d.and_hms_opt(0, 0, 0))
+                        .map(|dt| dt.and_utc())
+                })
+                .map(Some)
+                .ok_or_else(|| field_error(field, format!("unrecognized date '{}'", s)))
+        }
+        Value::Number(n) => n
+            .as_i64()
+            .filter(|secs| (MIN_EPOCH_SECONDS..MAX_EPOCH_SECONDS).contains(secs))
+            .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+            .map(Some)
+            .ok_or_else(|| field_error(field, format!("epoch seconds out of range: {}", n))),
+        other => Err(field_error(field, format!("expected date string or epoch seconds, got {}", type_name(other)))),
+    }
+}
+
+/// Документ в карантине (GET /osdr/quarantine)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsdrQuarantined {
+    pub id: i64,
+    pub dataset_id: Option<String>,
+    /// Версия правил, по которой документ отклонён
+    pub schema_version: i32,
+    pub errors: Vec<OsdrFieldError>,
+    pub raw: Value,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    /// Сколько синхронизаций документ приходил в этом виде
+    pub occurrences: i32,
+}
+
+/// Страница карантина
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OsdrQuarantinePage {
+    pub items: Vec<OsdrQuarantined>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
+}
+
+/// Итог записи одной пачки
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsdrBatchResult {
+    pub inserted: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
+/// Отчёт синхронизации OSDR (GET /osdr/sync)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsdrSyncReport {
+    pub started_at: DateTime<Utc>,
+    /// Записей в каталоге NASA (после удаления повторов dataset_id)
+    pub fetched: usize,
+    pub inserted: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    /// Записи из пачек, которые не удалось сохранить
+    pub failed: usize,
+    /// Отклонено нормализацией (см. /osdr/quarantine)
+    pub quarantined: usize,
+    pub batches: usize,
+    /// Страница, с которой начат обход (больше 1 - продолжение прерванного)
+    pub start_page: u32,
+    /// Пройдено страниц
+    pub pages: u32,
+    /// Каталог пройден до конца; иначе следующая синхронизация продолжит с next_page
+    pub complete: bool,
+    pub next_page: Option<u32>,
+    /// Ошибка загрузки страницы, на которой остановился обход
+    pub page_error: Option<OsdrPageError>,
+    /// Загружено манифестов файлов / файлов в них
+    pub manifests: usize,
+    pub files: usize,
+    pub manifest_failed: usize,
+    pub duration_ms: i64,
+}
+
+/// Тип изменения датасета, записанного в историю
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OsdrChangeKind {
+    /// Датасет впервые получен при синхронизации
+    Created,
+    /// Документ изменился с прошлой синхронизации
+    Updated,
+}
+
+impl OsdrChangeKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "created" => Some(Self::Created),
+            "updated" => Some(Self::Updated),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonChangeOp {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// Изменение одного значения документа; path - JSON Pointer (RFC 6901)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonChange {
+    pub path: String,
+    pub op: JsonChangeOp,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+/// Версия датасета из osdr_item_versions (без снимка raw)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OsdrVersion {
+    pub id: i64,
+    pub item_id: i64,
+    pub dataset_id: Option<String>,
+    /// Номер версии датасета, с единицы
+    pub version: i32,
+    pub change: OsdrChangeKind,
+    pub title: Option<String>,
+    pub status: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Отличия от предыдущей версии (пусто для created)
+    pub diff: Vec<JsonChange>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Файл из манифеста датасета (таблица osdr_files)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OsdrFile {
+    pub id: i64,
+    pub dataset_id: String,
+    pub name: String,
+    pub size_bytes: Option<i64>,
+    pub category: Option<String>,
+    pub subcategory: Option<String>,
+    /// Адрес файла у NASA (скачивание - через /osdr/:dataset_id/files/:id)
+    pub url: String,
+    pub checksum: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Файл манифеста до сохранения
+#[derive(Debug, Clone, PartialEq)]
+pub struct OsdrFileEntry {
+    pub name: String,
+    pub size_bytes: Option<i64>,
+    pub category: Option<String>,
+    pub subcategory: Option<String>,
+    pub url: String,
+    pub checksum: Option<String>,
+}
+
+/// Ответ GET /osdr/:dataset_id/files
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OsdrFileList {
+    pub dataset_id: String,
+    /// Когда манифест загружен; None - ещё не загружался
+    pub synced_at: Option<DateTime<Utc>>,
+    pub total_size_bytes: i64,
+    pub files: Vec<OsdrFile>,
+}
+
+/// Файлы из ответа эндпоинта списка файлов. Поддерживаются форматы
+/// osdr.nasa.gov ({"studies": {"OSD-N": {"study_files": [...]}}}),
+/// visualization API ({"OSD-N": {"files": {"имя": {"URL": ...}}}}) и простой массив.
+/// Относительные URL дополняются `origin` (scheme://host). Имя файла уникально в датасете:
+/// из повторов остаётся первый.
+pub fn parse_file_manifest(json: &Value, origin: &str) -> Vec<OsdrFileEntry> {
+    let mut entries = Vec::new();
+    let mut names = std::collections::HashSet::new();
+    let mut push = |name: Option<String>, item: &Value| {
+        let Some(name) = name.or_else(|| lookup_first(item, &["file name", "filename", "name", "file"])) else {
+            return;
+        };
+        let Some(url) = lookup_first(item, &["remote url", "url", "download url", "link"]) else {
+            return;
+        };
+        if !names.insert(name.clone()) {
+            return;
+        }
+        entries.push(OsdrFileEntry {
+            name,
+            size_bytes: lookup_i64(item, &["file size", "size", "bytes"]),
+            category: lookup_first(item, &["category", "data type"]),
+            subcategory: lookup_first(item, &["subcategory"]),
+            url: absolute_url(origin, &url),
+            checksum: lookup_first(item, &["md5", "md5sum", "checksum", "sha256"]),
+        });
+    };
+
+    let studies: Vec<&Value> = match json.get("studies").and_then(|v| v.as_object()) {
+        Some(map) => map.values().collect(),
+        None => match json {
+            Value::Object(map) => map.values().filter(|v| v.is_object()).chain([json]).collect(),
+            _ => vec![json],
+        },
+    };
+    for study in studies {
+        let files = ["study_files", "files"].iter().find_map(|k| study.get(*k)).unwrap_or(study);
+        match files {
+            Value::Array(items) => items.iter().filter(|i| i.is_object()).for_each(|i| push(None, i)),
+            Value::Object(map) if study.get("files").is_some() => {
+                map.iter().for_each(|(name, item)| push(Some(name.clone()), item))
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+fn absolute_url(origin: &str, url: &str) -> String {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        url.to_string()
+    } else {
+        format!("{}/{}", origin.trim_end_matches('/'), url.trim_start_matches('/'))
+    }
+}
+
+/// Ответ GET /osdr/:dataset_id/history
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OsdrHistory {
+    pub dataset_id: String,
+    pub item_id: i64,
+    /// От новых к старым
+    pub versions: Vec<OsdrVersion>,
+}
+
+/// Ответ GET /osdr/changes
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OsdrChanges {
+    pub since: DateTime<Utc>,
+    /// Число изменений после `since` (items ограничен limit)
+    pub total: i64,
+    pub items: Vec<OsdrVersion>,
+}
+
+/// Отличия `new` от `old`: объекты сравниваются по ключам, массивы одной длины - поэлементно,
+/// остальное - целиком
+pub fn json_diff(old: &Value, new: &Value) -> Vec<JsonChange> {
+    let mut changes = Vec::new();
+    diff_into(String::new(), old, new, &mut changes);
+    changes
+}
+
+fn diff_into(path: String, old: &Value, new: &Value, out: &mut Vec<JsonChange>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, old_value) in a {
+                let child = pointer_child(&path, key);
+                match b.get(key) {
+                    Some(new_value) => diff_into(child, old_value, new_value, out),
+                    None => out.push(JsonChange {
+                        path: child,
+                        op: JsonChangeOp::Removed,
+                        old: Some(old_value.clone()),
+                        new: None,
+                    }),
+                }
+            }
+            for (key, new_value) in b.iter().filter(|(k, _)| !a.contains_key(*k)) {
+                out.push(JsonChange {
+                    path: pointer_child(&path, key),
+                    op: JsonChangeOp::Added,
+                    old: None,
+                    new: Some(new_value.clone()),
+                });
+            }
+        }
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+            for (index, (old_value, new_value)) in a.iter().zip(b).enumerate() {
+                diff_into(format!("{}/{}", path, index), old_value, new_value, out);
+            }
+        }
+        _ if old != new => out.push(JsonChange {
+            path,
+            op: JsonChangeOp::Changed,
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// Экранирование ключа для JSON Pointer: '~' -> "~0", '/' -> "~1"
+fn pointer_child(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn metadata_from_flat_and_nested_keys() {
+        let raw = json!({
+            "title": "Rodent Research 1",
+            "Study_Description": "  Mice flown on the ISS  ",
+            "metadata": {
+                "organism": ["Mus musculus", "Mus musculus"],
+                "study assay technology type": "RNA Sequencing (RNA-Seq)",
+                "study factor name": [{"name": "Spaceflight"}, {"name": "Time"}],
+                "mission": {"Mission Name": "SpaceX-4", "Start Date": "2014-09-21"}
+            }
+        });
+
+        let meta = OsdrMetadata::from_raw(&raw);
+
+        assert_eq!(meta.description.as_deref(), Some("Mice flown on the ISS"));
+        assert_eq!(meta.organisms, vec!["Mus musculus"]);
+        assert_eq!(meta.assay_types, vec!["RNA Sequencing (RNA-Seq)"]);
+        assert_eq!(meta.factors, vec!["Spaceflight", "Time"]);
+        assert_eq!(meta.mission.as_deref(), Some("SpaceX-4"));
+    }
+
+    #[test]
+    fn dataset_normalizes_assays_mission_files_and_links() {
+        let item = OsdrItem {
+            id: 1,
+            dataset_id: Some("OSD-48".to_string()),
+            title: Some("RR-1 liver".to_string()),
+            status: None,
+            updated_at: None,
+            inserted_at: Utc::now(),
+            raw: json!({
+                "REST_URL": "https://visualization.osdr.nasa.gov/biodata/api/v2/dataset/OSD-48/",
+                "metadata": {
+                    "study assay measurement type": ["transcription profiling", "protein expression profiling"],
+                    "study assay technology type": ["RNA Sequencing (RNA-Seq)", "mass spectrometry"],
+                    "flight program": "International Space Station (ISS)",
+                    "mission": {"name": "SpaceX-4", "start date": "09/21/2014", "end date": "10/25/2014"},
+                    "study publication doi": "10.1038/s41598-017-00000-0",
+                    "files": [{"file_name": "counts.csv", "remote_url": "https://example/counts.csv", "file_size": "1024"}]
+                }
+            }),
+        };
+
+        let dataset = OsdrDataset::from_item(item);
+
+        assert_eq!(dataset.assays.len(), 2);
+        assert_eq!(dataset.assays[1].technology_type.as_deref(), Some("mass spectrometry"));
+        let mission = dataset.mission.unwrap();
+        assert_eq!(mission.name.as_deref(), Some("SpaceX-4"));
+        assert_eq!(mission.flight_program.as_deref(), Some("International Space Station (ISS)"));
+        assert_eq!(dataset.files[0].size_bytes, Some(1024));
+        let rels: Vec<&str> = dataset.links.iter().map(|l| l.rel.as_str()).collect();
+        assert_eq!(rels, vec!["api", "osdr", "publication"]);
+    }
+
+    #[test]
+    fn json_diff_reports_added_removed_and_changed_paths() {
+        let old = json!({
+            "title": "RR-1",
+            "metadata": {"organism": "Mus musculus", "files": ["a.csv", "b.csv"], "a/b": 1},
+            "legacy": true
+        });
+        let new = json!({
+            "title": "RR-1",
+            "metadata": {"organism": "Mus musculus", "files": ["a.csv", "c.csv"], "a/b": 2},
+            "doi": "10.1/x"
+        });
+
+        let diff = json_diff(&old, &new);
+        let summary: Vec<(&str, JsonChangeOp)> = diff.iter().map(|c| (c.path.as_str(), c.op)).collect();
+
+        assert_eq!(summary.len(), 4);
+        assert!(summary.contains(&("/metadata/files/1", JsonChangeOp::Changed)));
+        assert!(summary.contains(&("/metadata/a~1b", JsonChangeOp::Changed)));
+        assert!(summary.contains(&("/legacy", JsonChangeOp::Removed)));
+        assert!(summary.contains(&("/doi", JsonChangeOp::Added)));
+        assert!(json_diff(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn file_manifest_from_osdr_and_visualization_formats() {
+        let osdr = json!({
+            "hits": 1,
+            "studies": {"OSD-48": {"file_count": 2, "study_files": [
+                {"file_name": "counts.csv", "file_size": 2048, "category": "Study Metadata Files",
+                 "remote_url": "/geode-py/ws/studies/OSD-48/download?file=counts.csv"},
+                {"file_name": "counts.csv", "remote_url": "/duplicate/counts.csv"},
+                {"file_name": "no-url.txt"}
+            ]}}
+        });
+        let files = parse_file_manifest(&osdr, "https://osdr.nasa.gov");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].url, "https://osdr.nasa.gov/geode-py/ws/studies/OSD-48/download?file=counts.csv");
+        assert_eq!(files[0].size_bytes, Some(2048));
+        assert_eq!(files[0].category.as_deref(), Some("Study Metadata Files"));
+
+        let visualization = json!({"OSD-48": {"files": {
+            "reads.fastq.gz": {"URL": "https://example.org/reads.fastq.gz", "md5": "abc"}
+        }}});
+        let files = parse_file_manifest(&visualization, "https://visualization.osdr.nasa.gov");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].name, "reads.fastq.gz");
+        assert_eq!(files[0].checksum.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn export_row_text_fields_follow_columns() {
+        let row = OsdrExportRow {
+            id: 7,
+            dataset_id: Some("OSD-7".to_string()),
+            title: Some("Rodent Research".to_string()),
+            status: None,
+            mission: Some("RR-1".to_string()),
+            organisms: vec!["Mus musculus".to_string(), "Rattus".to_string()],
+            assay_types: Vec::new(),
+            factors: vec!["Spaceflight".to_string()],
+            updated_at: Some(Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap()),
+            inserted_at: Utc.with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap(),
+            description: None,
+        };
+
+        let fields = row.text_fields();
+        assert_eq!(fields.len(), OSDR_EXPORT_COLUMNS.len());
+        assert_eq!(fields[0], "7");
+        assert_eq!(fields[3], "");
+        assert_eq!(fields[5], "Mus musculus; Rattus");
+        assert_eq!(fields[8], "2024-03-01T12:00:00+00:00");
+        assert_eq!(OsdrExportFormat::parse("xlsx"), Some(OsdrExportFormat::Xlsx));
+        assert_eq!(OsdrExportFormat::parse("xls"), None);
+    }
+
+    #[test]
+    fn normalize_accepts_mapped_fields() {
+        let record = OsdrRecord::normalize(json!({
+            "id": 42,
+            "name": "  Rodent Research  ",
+            "state": "",
+            "modified": "2024-03-01"
+        }))
+        .unwrap();
+        assert_eq!(record.dataset_id, "42");
+        assert_eq!(record.title.as_deref(), Some("Rodent Research"));
+        assert_eq!(record.status, None);
+        assert_eq!(record.updated_at, Some(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()));
+
+        let record = OsdrRecord::normalize(json!({"dataset_id": "OSD-1", "updated": 1_700_000_000})).unwrap();
+        assert_eq!(record.updated_at, Some(Utc.timestamp_opt(1_700_000_000, 0).unwrap()));
+    }
+
+    #[test]
+    fn normalize_rejects_invalid_fields() {
+        let rejected = OsdrRecord::normalize(json!({
+            "dataset_id": "OSD-7",
+            "title": ["not", "text"],
+            "updated_at": 1_700_000_000_000i64
+        }))
+        .unwrap_err();
+        assert_eq!(rejected.dataset_id.as_deref(), Some("OSD-7"));
+        let fields: Vec<&str> = rejected.errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["title", "updated_at"]);
+
+        let rejected = OsdrRecord::normalize(json!({"title": "orphan", "updated": "yesterday"})).unwrap_err();
+        assert_eq!(rejected.dataset_id, None);
+        let fields: Vec<&str> = rejected.errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["updated", "dataset_id"]);
+
+        let rejected = OsdrRecord::normalize(json!({"id": 1.5})).unwrap_err();
+        assert_eq!(rejected.errors[0].field, "id");
+
+        let rejected = OsdrRecord::normalize(json!("OSD-1")).unwrap_err();
+        assert_eq!(rejected.errors[0].field, "$");
+    }
+
+    #[test]
+    fn catalogue_page_items_and_next_links() {
+        let url = "https://api.example/v2/datasets/?format=json&page=2";
+
+        let keyed = parse_catalogue_page(json!({"OSD-1": {"title": "a"}, "OSD-2": {"title": "b"}}), url);
+        assert_eq!(keyed.items.len(), 2);
+        assert_eq!(keyed.items[0]["dataset_id"], "OSD-1");
+        assert_eq!(keyed.next_url, None);
+
+        let drf = parse_catalogue_page(
+            json!({"count": 3, "next": "/v2/datasets/?page=3", "results": [{"id": 1}, {"id": 2}]}),
+            url,
+        );
+        assert_eq!(drf.items.len(), 2);
+        assert_eq!(drf.next_url.as_deref(), Some("https://api.example/v2/datasets/?page=3"));
+
+        let numbered = parse_catalogue_page(json!({"page": 2, "total_pages": 5, "items": []}), url);
+        assert_eq!(
+            numbered.next_url.as_deref(),
+            Some("https://api.example/v2/datasets/?format=json&page=3")
+        );
+
+        let last = parse_catalogue_page(json!({"page": 5, "total_pages": 5, "items": [], "next": null}), url);
+        assert_eq!(last.next_url, None);
+    }
+
+    #[test]
+    fn walk_stops_at_page_cap_and_self_link() {
+        let page = |next: &str| OsdrCataloguePage { items: Vec::new(), next_url: Some(next.to_string()) };
+        let start = OsdrCursor { url: "https://api.example/?page=1".to_string(), page: 1 };
+
+        // Лимит страниц: обход останавливается, но позиция для продолжения сохраняется
+        let mut walk = OsdrWalk::new(start.clone(), 2);
+        walk.advance(&page("https://api.example/?page=2"));
+        assert_eq!(walk.pending().map(|c| c.page), Some(2));
+        walk.advance(&page("https://api.example/?page=3"));
+        assert_eq!(walk.pending(), None);
+        let resume = walk.resume_cursor().unwrap();
+        assert_eq!((resume.url.as_str(), resume.page), ("https://api.example/?page=3", 3));
+
+        // Ссылка на саму себя - конец каталога
+        let mut walk = OsdrWalk::new(start.clone(), 10);
+        walk.advance(&page(&start.url));
+        assert_eq!(walk.pending(), None);
+        assert_eq!(walk.resume_cursor(), None);
+
+        // Без ссылки на следующую страницу - тоже конец
+        let mut walk = OsdrWalk::new(start, 10);
+        walk.advance(&OsdrCataloguePage { items: Vec::new(), next_url: None });
+        assert_eq!(walk.resume_cursor(), None);
+    }
+
+    #[test]
+    fn walk_stays_on_failed_page() {
+        let start = OsdrCursor { url: "https://api.example/?page=1".to_string(), page: 1 };
+        let mut walk = OsdrWalk::new(start, 5);
+        walk.advance(&OsdrCataloguePage {
+            items: Vec::new(),
+            next_url: Some("https://api.example/?page=2".to_string()),
+        });
+
+        // Загрузка страницы 2 не удалась: advance не вызывается
+        let failed = walk.pending().cloned().unwrap();
+        assert_eq!(walk.pending(), Some(&failed));
+        assert_eq!(walk.resume_cursor(), Some(&failed));
+        assert_eq!(failed.page, 2);
+    }
+
+    #[test]
+    fn metadata_empty_for_list_only_documents() {
+        let raw = json!({ "REST_URL": "https://visualization.osdr.nasa.gov/biodata/api/v2/dataset/OSD-1/" });
+        assert_eq!(OsdrMetadata::from_raw(&raw), OsdrMetadata::default());
+    }
 }
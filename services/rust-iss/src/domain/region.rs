@@ -0,0 +1,107 @@
+// Офлайн-определение страны/моря/океана под точкой по встроенному грубому набору контуров
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use crate::domain::geofence::point_in_ring;
+
+/// Контуры нарисованы вручную с точностью ~50-200 км; полигоны не пересекают антимеридиан
+const REGIONS_JSON: &str = include_str!("regions.json");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionKind {
+    Country,
+    Territory,
+    Sea,
+    Ocean,
+}
+
+impl RegionKind {
+    /// Порядок проверки: суша, затем моря, затем океаны
+    fn rank(&self) -> u8 {
+        match self {
+            RegionKind::Country | RegionKind::Territory => 0,
+            RegionKind::Sea => 1,
+            RegionKind::Ocean => 2,
+        }
+    }
+}
+
+/// Регион, над которым находится точка
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Region {
+    pub name: String,
+    /// ISO 3166-1 alpha-2 (только для суши)
+    pub code: Option<String>,
+    pub kind: RegionKind,
+}
+
+#[derive(Deserialize)]
+struct RegionEntry {
+    #[serde(flatten)]
+    region: Region,
+    polygons: Vec<Vec<[f64; 2]>>,
+}
+
+#[derive(Deserialize)]
+struct RegionDataset {
+    regions: Vec<RegionEntry>,
+}
+
+fn ring_area(ring: &[[f64; 2]]) -> f64 {
+    let mut sum = 0.0;
+    let mut j = ring.len().saturating_sub(1);
+    for i in 0..ring.len() {
+        sum += ring[j][0] * ring[i][1] - ring[i][0] * ring[j][1];
+        j = i;
+    }
+    (sum / 2.0).abs()
+}
+
+/// Набор разбирается один раз. Внутри одного вида сначала проверяются меньшие контуры,
+/// чтобы грубые границы соседей не перекрывали мелкие страны.
+fn dataset() -> &'static [RegionEntry] {
+    static DATASET: OnceLock<Vec<RegionEntry>> = OnceLock::new();
+    DATASET.get_or_init(|| {
+        let dataset: RegionDataset =
+            serde_json::from_str(REGIONS_JSON).expect("bundled regions.json must be valid");
+        let mut entries: Vec<(u8, f64, RegionEntry)> = dataset
+            .regions
+            .into_iter()
+            .map(|e| (e.region.kind.rank(), e.polygons.iter().map(|r| ring_area(r)).sum(), e))
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        entries.into_iter().map(|(_, _, e)| e).collect()
+    })
+}
+
+/// Регион под точкой; None - точка не попала ни в один контур (пробелы грубого набора)
+pub fn locate(latitude: f64, longitude: f64) -> Option<Region> {
+    if !latitude.is_finite() || !longitude.is_finite() {
+        return None;
+    }
+    dataset()
+        .iter()
+        .find(|e| e.polygons.iter().any(|ring| point_in_ring(ring, latitude, longitude)))
+        .map(|e| e.region.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(latitude: f64, longitude: f64) -> Option<String> {
+        locate(latitude, longitude).map(|r| r.name)
+    }
+
+    #[test]
+    fn locates_land_seas_and_oceans() {
+        assert_eq!(name(55.75, 37.6).as_deref(), Some("Russia"));
+        assert_eq!(name(27.7, 85.3).as_deref(), Some("Nepal"));
+        assert_eq!(name(-33.87, 151.2).as_deref(), Some("Australia"));
+        assert_eq!(name(43.0, 35.0).as_deref(), Some("Black Sea"));
+        assert_eq!(name(0.0, -30.0).as_deref(), Some("Atlantic Ocean"));
+        assert_eq!(name(0.0, -150.0).as_deref(), Some("Pacific Ocean"));
+        assert_eq!(name(-20.0, 80.0).as_deref(), Some("Indian Ocean"));
+        assert_eq!(locate(55.75, 37.6).unwrap().code.as_deref(), Some("RU"));
+    }
+}
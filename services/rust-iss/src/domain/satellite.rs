@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// NORAD ID МКС - объект по умолчанию для /last, /iss/* и стрима
+pub const ISS_NORAD_ID: i32 = 25544;
+
+/// Источник TLE объекта в space_cache (для МКС - прежний "iss_tle")
+pub fn tle_cache_source(norad_id: i32) -> String {
+    if norad_id == ISS_NORAD_ID {
+        "iss_tle".to_string()
+    } else {
+        format!("tle_{}", norad_id)
+    }
+}
+
+/// Отслеживаемый объект из реестра tracked_objects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedObject {
+    pub norad_id: i32,
+    pub name: String,
+    /// Интервал опроса, с (None - ISS_EVERY_SECONDS)
+    pub poll_every_seconds: Option<i64>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TrackedObject {
+    pub fn from_row(row: &sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(Self {
+            norad_id: row.try_get("norad_id")?,
+            name: row.try_get("name")?,
+            poll_every_seconds: row.try_get("poll_every_seconds")?,
+            enabled: row.try_get("enabled")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+/// Тело запроса на добавление/изменение объекта
+#[derive(Debug, Deserialize)]
+pub struct TrackedObjectInput {
+    pub norad_id: i32,
+    pub name: String,
+    pub poll_every_seconds: Option<i64>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
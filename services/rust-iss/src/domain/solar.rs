@@ -0,0 +1,205 @@
+// Положение Солнца (упрощённая теория, точность ~0.01°) и тень Земли
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::domain::iss::orbit::{geodetic_to_ecef, julian_date, norm, teme_to_ecef, Geodetic};
+
+/// Астрономическая единица, км
+const AU_KM: f64 = 149_597_870.7;
+/// Экваториальный радиус Земли для теста тени, км
+const EARTH_RADIUS_KM: f64 = 6378.137;
+
+/// Вектор Земля→Солнце в инерциальной системе (экватор даты), км
+pub fn sun_position_eci(at: DateTime<Utc>) -> [f64; 3] {
+    let tut1 = (julian_date(at) - 2_451_545.0) / 36525.0;
+    let mean_longitude = (280.460 + 36000.771 * tut1).rem_euclid(360.0);
+    let mean_anomaly = (357.5291092 + 35999.05034 * tut1).rem_euclid(360.0).to_radians();
+    let ecliptic_longitude = (mean_longitude
+        + 1.914666471 * mean_anomaly.sin()
+        + 0.019994643 * (2.0 * mean_anomaly).sin())
+    .to_radians();
+    let obliquity = (23.439291 - 0.0130042 * tut1).to_radians();
+    let distance_au = 1.000140612
+        - 0.016708617 * mean_anomaly.cos()
+        - 0.000139589 * (2.0 * mean_anomaly).cos();
+
+    let r = distance_au * AU_KM;
+    [
+        r * ecliptic_longitude.cos(),
+        r * obliquity.cos() * ecliptic_longitude.sin(),
+        r * obliquity.sin() * ecliptic_longitude.sin(),
+    ]
+}
+
+/// Освещён ли объект на инерциальной позиции `position` (цилиндрическая модель тени)
+pub fn is_sunlit(position: [f64; 3], at: DateTime<Utc>) -> bool {
+    in_sunlight(position, sun_position_eci(at))
+}
+
+/// Значение visibility в терминах wheretheiss.at
+pub fn visibility_label(sunlit: bool) -> &'static str {
+    if sunlit {
+        "daylight"
+    } else {
+        "eclipsed"
+    }
+}
+
+/// Освещён ли объект над точкой `geo` (высота - над эллипсоидом)
+pub fn is_sunlit_geodetic(geo: &Geodetic, at: DateTime<Utc>) -> bool {
+    in_sunlight(geodetic_to_ecef(geo), teme_to_ecef(sun_position_eci(at), at))
+}
+
+/// Подсолнечная точка (её широта равна склонению Солнца)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SubsolarPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+pub fn subsolar_point(at: DateTime<Utc>) -> SubsolarPoint {
+    let [x, y, z] = teme_to_ecef(sun_position_eci(at), at);
+    SubsolarPoint {
+        latitude: z.atan2((x * x + y * y).sqrt()).to_degrees(),
+        longitude: y.atan2(x).to_degrees(),
+    }
+}
+
+/// Высота Солнца над горизонтом в точке (сферическая Земля, без рефракции), градусы
+pub fn solar_elevation(latitude: f64, longitude: f64, at: DateTime<Utc>) -> f64 {
+    let sun = subsolar_point(at);
+    let (sin_lat, cos_lat) = latitude.to_radians().sin_cos();
+    let (sin_dec, cos_dec) = sun.latitude.to_radians().sin_cos();
+    let hour_angle = (longitude - sun.longitude).to_radians();
+    (sin_lat * sin_dec + cos_lat * cos_dec * hour_angle.cos())
+        .clamp(-1.0, 1.0)
+        .asin()
+        .to_degrees()
+}
+
+/// Линия терминатора [lon, lat] от -180 до 180 с шагом `step_deg`
+pub fn terminator_line(at: DateTime<Utc>, step_deg: f64) -> Vec<[f64; 2]> {
+    let sun = subsolar_point(at);
+    // В равноденствие tan(dec) -> 0: терминатор вырождается в меридианы, ограничиваем снизу
+    let mut dec = sun.latitude.to_radians();
+    if dec.abs() < 1e-6 {
+        dec = 1e-6_f64.copysign(dec);
+    }
+
+    let steps = (360.0 / step_deg).ceil() as usize;
+    (0..=steps)
+        .map(|i| {
+            let lon = (-180.0 + i as f64 * step_deg).min(180.0);
+            let hour_angle = (lon - sun.longitude).to_radians();
+            let lat = (-hour_angle.cos() / dec.tan()).atan().to_degrees();
+            [lon, lat]
+        })
+        .collect()
+}
+
+/// Ночное полушарие как замкнутое кольцо [lon, lat] против часовой стрелки (RFC 7946)
+pub fn night_ring(at: DateTime<Utc>, step_deg: f64) -> Vec<[f64; 2]> {
+    let line = terminator_line(at, step_deg);
+    // Летом северного полушария в тени южный полюс, и наоборот
+    if subsolar_point(at).latitude >= 0.0 {
+        let mut ring = vec![[-180.0, -90.0], [180.0, -90.0]];
+        ring.extend(line.iter().rev());
+        ring.push([-180.0, -90.0]);
+        ring
+    } else {
+        let mut ring = line.clone();
+        ring.push([180.0, 90.0]);
+        ring.push([-180.0, 90.0]);
+        ring.push(line[0]);
+        ring
+    }
+}
+
+/// GeoJSON FeatureCollection: ночь (Polygon), терминатор (LineString), подсолнечная точка (Point)
+pub fn terminator_geojson(at: DateTime<Utc>, step_deg: f64) -> Value {
+    let sun = subsolar_point(at);
+    let at_str = at.to_rfc3339_opts(SecondsFormat::Secs, true);
+    json!({
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "properties": { "kind": "night", "at": at_str },
+                "geometry": { "type": "Polygon", "coordinates": [night_ring(at, step_deg)] },
+            },
+            {
+                "type": "Feature",
+                "properties": { "kind": "terminator", "at": at_str },
+                "geometry": { "type": "LineString", "coordinates": terminator_line(at, step_deg) },
+            },
+            {
+                "type": "Feature",
+                "properties": { "kind": "subsolar_point", "at": at_str },
+                "geometry": { "type": "Point", "coordinates": [sun.longitude, sun.latitude] },
+            },
+        ],
+    })
+}
+
+/// Цилиндрическая тень Земли; `position` и `sun` в одной системе координат
+fn in_sunlight(position: [f64; 3], sun: [f64; 3]) -> bool {
+    let sun_len = norm(sun);
+    let sun_unit = [sun[0] / sun_len, sun[1] / sun_len, sun[2] / sun_len];
+
+    let along = position[0] * sun_unit[0] + position[1] * sun_unit[1] + position[2] * sun_unit[2];
+    if along >= 0.0 {
+        return true;
+    }
+
+    let perpendicular = [
+        position[0] - along * sun_unit[0],
+        position[1] - along * sun_unit[1],
+        position[2] - along * sun_unit[2],
+    ];
+    norm(perpendicular) > EARTH_RADIUS_KM
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::geofence::point_in_ring;
+    use chrono::TimeZone;
+
+    #[test]
+    fn subsolar_point_at_june_solstice_noon() {
+        let at = Utc.with_ymd_and_hms(2024, 6, 20, 12, 0, 0).unwrap();
+        let sun = subsolar_point(at);
+
+        assert!((sun.latitude - 23.43).abs() < 0.1);
+        // Уравнение времени в июне ~ -1.5 мин: Солнце чуть восточнее Гринвича
+        assert!(sun.longitude.abs() < 1.0);
+        assert!((solar_elevation(sun.latitude, sun.longitude, at) - 90.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn night_ring_covers_antisolar_side() {
+        let at = Utc.with_ymd_and_hms(2024, 6, 20, 12, 0, 0).unwrap();
+        let ring = night_ring(at, 2.0);
+
+        assert_eq!(ring.first(), ring.last());
+        // Тихий океан в полночь - ночь, Африка в полдень - день,
+        // Арктика в июне - полярный день, Антарктида - полярная ночь
+        assert!(point_in_ring(&ring, 0.0, 179.0));
+        assert!(!point_in_ring(&ring, 0.0, 10.0));
+        assert!(!point_in_ring(&ring, 85.0, 179.0));
+        assert!(point_in_ring(&ring, -85.0, 10.0));
+    }
+
+    #[test]
+    fn geodetic_sunlight_matches_ground_and_orbit() {
+        let at = Utc.with_ymd_and_hms(2024, 6, 20, 12, 0, 0).unwrap();
+        let noon = Geodetic { latitude: 0.0, longitude: 0.0, altitude_km: 420.0 };
+        let midnight = Geodetic { latitude: 0.0, longitude: 180.0, altitude_km: 420.0 };
+
+        assert!(is_sunlit_geodetic(&noon, at));
+        assert!(!is_sunlit_geodetic(&midnight, at));
+        // Полярный день над Арктикой, полярная ночь над Южным океаном
+        assert!(is_sunlit_geodetic(&Geodetic { latitude: 80.0, ..midnight }, at));
+        assert!(!is_sunlit_geodetic(&Geodetic { latitude: -60.0, ..midnight }, at));
+    }
+}
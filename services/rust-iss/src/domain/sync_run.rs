@@ -0,0 +1,222 @@
+// Журнал запусков задач загрузки данных (планировщик и ручные вызовы)
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::domain::{osdr::OsdrSyncReport, ApiError};
+
+/// Имена задач в sync_runs
+pub const JOB_ISS_FETCH: &str = "iss_fetch";
+pub const JOB_ISS_BACKFILL: &str = "iss_backfill";
+pub const JOB_TLE_REFRESH: &str = "tle_refresh";
+pub const JOB_OSDR_SYNC: &str = "osdr_sync";
+pub const JOB_APOD: &str = "apod";
+pub const JOB_NEO: &str = "neo";
+pub const JOB_DONKI: &str = "donki";
+pub const JOB_SPACEX: &str = "spacex";
+pub const JOB_SPACE_REFRESH: &str = "space_refresh";
+
+/// Кто запустил задачу
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunTrigger {
+    Scheduler,
+    Manual,
+}
+
+impl RunTrigger {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "scheduler" => Some(Self::Scheduler),
+            "manual" => Some(Self::Manual),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Scheduler => "scheduler",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunOutcome {
+    /// Запуск ещё идёт (или процесс остановился, не дописав итог)
+    Running,
+    Success,
+    /// Часть элементов не обработана
+    Partial,
+    Failed,
+}
+
+impl RunOutcome {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "running" => Some(Self::Running),
+            "success" => Some(Self::Success),
+            "partial" => Some(Self::Partial),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Success => "success",
+            Self::Partial => "partial",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// Итог успешно завершившейся задачи: счётчики и подробности
+#[derive(Debug, Clone, Default)]
+pub struct RunSummary {
+    pub items: i64,
+    pub failed: i64,
+    pub details: Option<Value>,
+    /// Первая ошибка по элементам: (код, сообщение)
+    pub error: Option<(String, String)>,
+}
+
+impl RunSummary {
+    pub fn items(items: i64) -> Self {
+        Self { items, ..Self::default() }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// Учесть ошибку по одному элементу
+    pub fn record_failure(&mut self, error: &ApiError) {
+        self.failed += 1;
+        if self.error.is_none() {
+            self.error = Some((error.code().to_string(), error.message()));
+        }
+    }
+
+    pub fn outcome(&self) -> RunOutcome {
+        match (self.items, self.failed) {
+            (_, 0) => RunOutcome::Success,
+            (0, _) => RunOutcome::Failed,
+            _ => RunOutcome::Partial,
+        }
+    }
+
+    /// Счётчики синхронизации OSDR; отчёт целиком - в details
+    /// Недогруженная страница каталога считается одним неудачным элементом
+    pub fn from_osdr(report: &OsdrSyncReport) -> Self {
+        Self {
+            items: (report.inserted + report.updated + report.unchanged) as i64,
+            failed: (report.failed + report.manifest_failed + report.page_error.is_some() as usize) as i64,
+            details: serde_json::to_value(report).ok(),
+            error: report.page_error.as_ref().map(|e| (e.code.clone(), e.message.clone())),
+        }
+    }
+}
+
+/// Запись журнала sync_runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncRun {
+    pub id: i64,
+    pub job: String,
+    pub trigger: RunTrigger,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<i64>,
+    pub outcome: RunOutcome,
+    /// Обработано элементов
+    pub items: Option<i64>,
+    pub failed_items: Option<i64>,
+    pub error_code: Option<String>,
+    pub error_message: Option<String>,
+    pub details: Option<Value>,
+}
+
+/// Фильтр журнала запусков
+#[derive(Debug, Clone, Default)]
+pub struct SyncRunFilter {
+    pub job: Option<String>,
+    pub trigger: Option<RunTrigger>,
+    pub outcome: Option<RunOutcome>,
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Ответ GET /admin/jobs/runs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncRunPage {
+    pub items: Vec<SyncRun>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::osdr::OsdrPageError;
+
+    fn osdr_report(inserted: usize, failed: usize, page_error: Option<OsdrPageError>) -> OsdrSyncReport {
+        OsdrSyncReport {
+            started_at: Utc::now(),
+            fetched: inserted + failed,
+            inserted,
+            updated: 0,
+            unchanged: 1,
+            failed,
+            quarantined: 0,
+            batches: 1,
+            start_page: 1,
+            pages: 2,
+            complete: page_error.is_none(),
+            next_page: page_error.as_ref().map(|e| e.page),
+            page_error,
+            manifests: 0,
+            files: 0,
+            manifest_failed: 0,
+            duration_ms: 10,
+        }
+    }
+
+    #[test]
+    fn outcome_follows_item_counts() {
+        assert_eq!(RunSummary::items(3).outcome(), RunOutcome::Success);
+        assert_eq!(RunSummary::default().outcome(), RunOutcome::Success);
+
+        let mut summary = RunSummary::items(2);
+        summary.record_failure(&ApiError::NotFound("first".to_string()));
+        summary.record_failure(&ApiError::InternalError("second".to_string()));
+        assert_eq!(summary.outcome(), RunOutcome::Partial);
+        assert_eq!(summary.failed, 2);
+        assert_eq!(summary.error.as_ref().map(|(_, message)| message.as_str()), Some("first"));
+
+        summary.items = 0;
+        assert_eq!(summary.outcome(), RunOutcome::Failed);
+    }
+
+    #[test]
+    fn osdr_page_error_counts_as_failure() {
+        let clean = RunSummary::from_osdr(&osdr_report(4, 0, None));
+        assert_eq!((clean.items, clean.failed), (5, 0));
+        assert_eq!(clean.outcome(), RunOutcome::Success);
+        assert!(clean.error.is_none());
+
+        let page_error = OsdrPageError {
+            page: 3,
+            code: "UPSTREAM_503".to_string(),
+            message: "Service Unavailable".to_string(),
+        };
+        let stopped = RunSummary::from_osdr(&osdr_report(4, 1, Some(page_error)));
+        assert_eq!((stopped.items, stopped.failed), (5, 2));
+        assert_eq!(stopped.outcome(), RunOutcome::Partial);
+        assert_eq!(stopped.error, Some(("UPSTREAM_503".to_string(), "Service Unavailable".to_string())));
+        assert_eq!(stopped.details.as_ref().and_then(|d| d["next_page"].as_u64()), Some(3));
+    }
+}
@@ -0,0 +1,61 @@
+use axum::{extract::{Query, State}, Json};
+use serde::Deserialize;
+use crate::{
+    domain::{
+        ApiResponse, ApiError,
+        sync_run::{RunOutcome, RunTrigger, SyncRunFilter, SyncRunPage},
+    },
+    handlers::parse_datetime_opt,
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct RunsQuery {
+    job: Option<String>,
+    trigger: Option<String>,
+    outcome: Option<String>,
+    since: Option<String>,
+    #[serde(default = "default_page")]
+    page: i64,
+    #[serde(default = "default_per_page")]
+    per_page: i64,
+}
+
+fn default_page() -> i64 {
+    1
+}
+
+fn default_per_page() -> i64 {
+    50
+}
+
+/// GET /admin/jobs/runs?job=&trigger=&outcome=&since=&page=&per_page= - журнал запусков задач
+/// trigger: scheduler | manual; outcome: running | success | partial | failed
+pub async fn list_runs(
+    Query(query): Query<RunsQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<SyncRunPage>>, ApiError> {
+    let trigger = match query.trigger.as_deref() {
+        None | Some("") => None,
+        Some(s) => Some(RunTrigger::parse(s).ok_or_else(|| {
+            ApiError::ValidationError("'trigger' must be 'scheduler' or 'manual'".to_string())
+        })?),
+    };
+    let outcome = match query.outcome.as_deref() {
+        None | Some("") => None,
+        Some(s) => Some(RunOutcome::parse(s).ok_or_else(|| {
+            ApiError::ValidationError(
+                "'outcome' must be one of: running, success, partial, failed".to_string(),
+            )
+        })?),
+    };
+    let filter = SyncRunFilter {
+        job: query.job.filter(|s| !s.trim().is_empty()),
+        trigger,
+        outcome,
+        since: parse_datetime_opt("since", query.since.as_deref())?,
+    };
+
+    let page = state.sync_run_service.list(filter, query.page, query.per_page).await?;
+    Ok(Json(ApiResponse::success(page)))
+}
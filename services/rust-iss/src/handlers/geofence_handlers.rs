@@ -0,0 +1,90 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use crate::{
+    domain::{ApiResponse, ApiError, geofence::{Geofence, GeofenceEvent, GeofenceInput}},
+    handlers::parse_datetime_opt,
+    AppState,
+};
+
+const DEFAULT_EVENTS_LIMIT: i64 = 100;
+
+/// Тело разбираем сами, чтобы ошибка JSON пришла в едином формате
+fn parse_input(body: &Bytes) -> Result<GeofenceInput, ApiError> {
+    serde_json::from_slice(body)
+        .map_err(|e| ApiError::ValidationError(format!("invalid geofence body: {}", e)))
+}
+
+/// GET /geofences - список геозон
+pub async fn list_geofences(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<Geofence>>>, ApiError> {
+    let geofences = state.geofence_service.list().await?;
+    Ok(Json(ApiResponse::success(geofences)))
+}
+
+/// GET /geofences/:id - одна геозона
+pub async fn get_geofence(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Geofence>>, ApiError> {
+    let geofence = state.geofence_service.get(id).await?;
+    Ok(Json(ApiResponse::success(geofence)))
+}
+
+/// POST /geofences - создать геозону
+/// {"name": "...", "shape": {"type": "circle", "latitude": .., "longitude": .., "radius_km": ..}}
+/// {"name": "...", "shape": {"type": "polygon", "coordinates": [[lon, lat], ...]}}
+pub async fn create_geofence(
+    State(state): State<AppState>,
+    body: Bytes,
+) -> Result<Json<ApiResponse<Geofence>>, ApiError> {
+    let geofence = state.geofence_service.create(parse_input(&body)?).await?;
+    Ok(Json(ApiResponse::success(geofence)))
+}
+
+/// PUT /geofences/:id - заменить имя и форму геозоны
+pub async fn update_geofence(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+    body: Bytes,
+) -> Result<Json<ApiResponse<Geofence>>, ApiError> {
+    let geofence = state.geofence_service.update(id, parse_input(&body)?).await?;
+    Ok(Json(ApiResponse::success(geofence)))
+}
+
+/// DELETE /geofences/:id - удалить геозону вместе с её событиями
+pub async fn delete_geofence(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    state.geofence_service.delete(id).await?;
+    Ok(Json(ApiResponse::success(serde_json::json!({ "deleted": id }))))
+}
+
+#[derive(Deserialize)]
+pub struct EventsQuery {
+    geofence_id: Option<i64>,
+    from: Option<String>,
+    to: Option<String>,
+    limit: Option<i64>,
+}
+
+/// GET /iss/events?geofence_id=&from=&to=&limit= - события входа/выхода МКС в геозоны
+pub async fn get_events(
+    Query(query): Query<EventsQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<GeofenceEvent>>>, ApiError> {
+    let from = parse_datetime_opt("from", query.from.as_deref())?;
+    let to = parse_datetime_opt("to", query.to.as_deref())?;
+
+    let events = state
+        .geofence_service
+        .events(query.geofence_id, from, to, query.limit.unwrap_or(DEFAULT_EVENTS_LIMIT))
+        .await?;
+
+    Ok(Json(ApiResponse::success(events)))
+}
@@ -1,25 +1,69 @@
-use axum::{extract::State, Json};
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
 use crate::{
-    domain::{ApiResponse, ApiError},
+    domain::{
+        ApiResponse, ApiError,
+        iss::ground_track::{self, TrackSource},
+        iss::altitude::AltitudeAnalysis,
+        iss::orbit::Geodetic,
+        iss::{IssHistory, IssIllumination, IssPasses, IssPrediction, IssRange, IssTrack, IssTrend, Observer},
+        satellite::ISS_NORAD_ID,
+        sync_run::{RunSummary, RunTrigger, JOB_ISS_FETCH},
+    },
+    handlers::parse_datetime_opt,
     AppState,
 };
 
+#[derive(Deserialize)]
+pub struct LastQuery {
+    limit: Option<i64>,
+    cursor: Option<i64>,
+}
+
 /// GET /last - получить последнюю позицию МКС
+/// GET /last?limit=N&cursor= - последние N позиций (как /iss/history)
 pub async fn get_last(
+    Query(query): Query<LastQuery>,
     State(state): State<AppState>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
-    let position = state.iss_service.get_last_position().await?;
+    last_for(&state, ISS_NORAD_ID, query).await
+}
+
+pub(crate) async fn last_for(
+    state: &AppState,
+    norad_id: i32,
+    query: LastQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    if query.limit.is_some() || query.cursor.is_some() {
+        let history = state
+            .iss_service
+            .get_history(norad_id, query.limit.unwrap_or(DEFAULT_HISTORY_LIMIT), query.cursor)
+            .await?;
+        let data = serde_json::to_value(history)
+            .map_err(|e| ApiError::InternalError(e.to_string()))?;
+        return Ok(Json(ApiResponse::success(data)));
+    }
+
+    let position = state.iss_service.get_last_position(norad_id).await?;
 
     match position {
         Some(pos) => {
             let data = serde_json::json!({
                 "id": pos.id,
+                "norad_id": pos.norad_id,
                 "fetched_at": pos.fetched_at,
                 "source_url": pos.source_url,
+                "provider": pos.provider,
                 "latitude": pos.latitude,
                 "longitude": pos.longitude,
                 "altitude": pos.altitude,
                 "velocity": pos.velocity,
+                "region": pos.region,
                 "payload": pos.payload,
             });
             Ok(Json(ApiResponse::success(data)))
@@ -31,14 +75,60 @@ pub async fn get_last(
     }
 }
 
+const DEFAULT_HISTORY_LIMIT: i64 = 10;
+
+#[derive(Deserialize)]
+pub struct HistoryQuery {
+    #[serde(default = "default_history_limit")]
+    limit: i64,
+    cursor: Option<i64>,
+}
+
+fn default_history_limit() -> i64 {
+    DEFAULT_HISTORY_LIMIT
+}
+
+/// GET /iss/history?limit=N&cursor= - история позиций МКС (от новых к старым)
+pub async fn get_history(
+    Query(query): Query<HistoryQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<IssHistory>>, ApiError> {
+    history_for(&state, ISS_NORAD_ID, query).await
+}
+
+pub(crate) async fn history_for(
+    state: &AppState,
+    norad_id: i32,
+    query: HistoryQuery,
+) -> Result<Json<ApiResponse<IssHistory>>, ApiError> {
+    let history = state.iss_service.get_history(norad_id, query.limit, query.cursor).await?;
+    Ok(Json(ApiResponse::success(history)))
+}
+
 /// GET /fetch - триггер ручной загрузки данных МКС
 pub async fn trigger_fetch(
     State(state): State<AppState>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
-    let position = state.iss_service.fetch_and_store().await?;
+    fetch_for(&state, ISS_NORAD_ID).await
+}
+
+pub(crate) async fn fetch_for(
+    state: &AppState,
+    norad_id: i32,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let position = state
+        .sync_run_service
+        .track(
+            JOB_ISS_FETCH,
+            RunTrigger::Manual,
+            state.iss_service.fetch_and_store(norad_id),
+            |_| RunSummary::items(1).with_details(serde_json::json!({ "norad_ids": [norad_id] })),
+        )
+        .await?;
 
     let data = serde_json::json!({
         "id": position.id,
+        "norad_id": position.norad_id,
         "fetched_at": position.fetched_at,
         "source_url": position.source_url,
         "latitude": position.latitude,
@@ -51,10 +141,338 @@ pub async fn trigger_fetch(
     Ok(Json(ApiResponse::success(data)))
 }
 
-/// GET /iss/trend - анализ движения МКС
+#[derive(Deserialize)]
+pub struct TrendQuery {
+    #[serde(default = "default_trend_hours")]
+    hours: i64,
+}
+
+fn default_trend_hours() -> i64 {
+    24
+}
+
+/// GET /iss/trend?hours=N - анализ движения МКС за окно
 pub async fn get_trend(
+    Query(query): Query<TrendQuery>,
     State(state): State<AppState>,
-) -> Result<Json<ApiResponse<crate::domain::iss::IssTrend>>, ApiError> {
-    let trend = state.iss_service.calculate_trend().await?;
+) -> Result<Json<ApiResponse<IssTrend>>, ApiError> {
+    trend_for(&state, ISS_NORAD_ID, query).await
+}
+
+pub(crate) async fn trend_for(
+    state: &AppState,
+    norad_id: i32,
+    query: TrendQuery,
+) -> Result<Json<ApiResponse<IssTrend>>, ApiError> {
+    let trend = state.iss_service.calculate_trend(norad_id, query.hours).await?;
     Ok(Json(ApiResponse::success(trend)))
 }
+
+#[derive(Deserialize)]
+pub struct RangeQuery {
+    from: Option<String>,
+    to: Option<String>,
+    // Алиасы, которые шлёт Laravel IssRangeRequest
+    start: Option<String>,
+    end: Option<String>,
+    #[serde(default = "default_range_limit")]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+    step: Option<i64>,
+}
+
+fn default_range_limit() -> i64 {
+    500
+}
+
+/// GET /iss/range?from=&to=&limit=&offset=&step= - позиции МКС за период
+pub async fn get_range(
+    Query(query): Query<RangeQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<IssRange>>, ApiError> {
+    range_for(&state, ISS_NORAD_ID, query).await
+}
+
+pub(crate) async fn range_for(
+    state: &AppState,
+    norad_id: i32,
+    query: RangeQuery,
+) -> Result<Json<ApiResponse<IssRange>>, ApiError> {
+    let from = parse_datetime_opt("from", query.from.as_deref().or(query.start.as_deref()))?;
+    let to = parse_datetime_opt("to", query.to.as_deref().or(query.end.as_deref()))?;
+
+    let range = state
+        .iss_service
+        .get_range(norad_id, from, to, query.limit, query.offset, query.step)
+        .await?;
+
+    Ok(Json(ApiResponse::success(range)))
+}
+
+#[derive(Deserialize)]
+pub struct PredictQuery {
+    at: Option<String>,
+}
+
+/// GET /iss/predict?at= - расчётное положение МКС (SGP4 по TLE)
+pub async fn predict(
+    Query(query): Query<PredictQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<IssPrediction>>, ApiError> {
+    predict_for(&state, ISS_NORAD_ID, query).await
+}
+
+pub(crate) async fn predict_for(
+    state: &AppState,
+    norad_id: i32,
+    query: PredictQuery,
+) -> Result<Json<ApiResponse<IssPrediction>>, ApiError> {
+    let at = parse_datetime_opt("at", query.at.as_deref())?;
+    let prediction = state.iss_service.predict(norad_id, at).await?;
+    Ok(Json(ApiResponse::success(prediction)))
+}
+
+#[derive(Deserialize)]
+pub struct TrackQuery {
+    from: Option<String>,
+    to: Option<String>,
+    #[serde(default = "default_track_step")]
+    step: i64,
+}
+
+fn default_track_step() -> i64 {
+    60
+}
+
+/// GET /iss/track?from=&to=&step= - расчётная трасса МКС
+pub async fn get_track(
+    Query(query): Query<TrackQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<IssTrack>>, ApiError> {
+    track_for(&state, ISS_NORAD_ID, query).await
+}
+
+pub(crate) async fn track_for(
+    state: &AppState,
+    norad_id: i32,
+    query: TrackQuery,
+) -> Result<Json<ApiResponse<IssTrack>>, ApiError> {
+    let from = parse_datetime_opt("from", query.from.as_deref())?;
+    let to = parse_datetime_opt("to", query.to.as_deref())?;
+    let track = state.iss_service.track(norad_id, from, to, query.step).await?;
+    Ok(Json(ApiResponse::success(track)))
+}
+
+#[derive(Deserialize)]
+pub struct PassesQuery {
+    // По умолчанию - Москва, как в AstroController
+    #[serde(default = "default_lat")]
+    lat: f64,
+    #[serde(default = "default_lon")]
+    lon: f64,
+    /// Высота наблюдателя, м
+    #[serde(default)]
+    alt: f64,
+    #[serde(default = "default_pass_days")]
+    days: i64,
+}
+
+fn default_lat() -> f64 {
+    55.7558
+}
+
+fn default_lon() -> f64 {
+    37.6176
+}
+
+fn default_pass_days() -> i64 {
+    3
+}
+
+/// GET /iss/passes?lat=&lon=&alt=&days= - пролёты МКС над наблюдателем
+pub async fn get_passes(
+    Query(query): Query<PassesQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<IssPasses>>, ApiError> {
+    passes_for(&state, ISS_NORAD_ID, query).await
+}
+
+pub(crate) async fn passes_for(
+    state: &AppState,
+    norad_id: i32,
+    query: PassesQuery,
+) -> Result<Json<ApiResponse<IssPasses>>, ApiError> {
+    let observer = Observer {
+        latitude: query.lat,
+        longitude: query.lon,
+        altitude_m: query.alt,
+    };
+    let passes = state.iss_service.passes(norad_id, observer, query.days).await?;
+    Ok(Json(ApiResponse::success(passes)))
+}
+
+#[derive(Deserialize)]
+pub struct IlluminationQuery {
+    at: Option<String>,
+    /// Координаты вместо расчёта по TLE (например, сохранённый замер без visibility)
+    lat: Option<f64>,
+    lon: Option<f64>,
+    /// Высота над эллипсоидом, км
+    alt: Option<f64>,
+}
+
+/// GET /iss/illumination?at=[&lat=&lon=&alt=] - на солнце ли МКС или в тени Земли
+pub async fn get_illumination(
+    Query(query): Query<IlluminationQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<IssIllumination>>, ApiError> {
+    illumination_for(&state, ISS_NORAD_ID, query).await
+}
+
+pub(crate) async fn illumination_for(
+    state: &AppState,
+    norad_id: i32,
+    query: IlluminationQuery,
+) -> Result<Json<ApiResponse<IssIllumination>>, ApiError> {
+    let at = parse_datetime_opt("at", query.at.as_deref())?;
+    let coordinates = match (query.lat, query.lon, query.alt) {
+        (Some(latitude), Some(longitude), Some(altitude_km)) => Some(Geodetic { latitude, longitude, altitude_km }),
+        (None, None, None) => None,
+        _ => {
+            return Err(ApiError::ValidationError(
+                "'lat', 'lon' and 'alt' must be given together".to_string(),
+            ))
+        }
+    };
+
+    let illumination = state.iss_service.illumination(norad_id, at, coordinates).await?;
+    Ok(Json(ApiResponse::success(illumination)))
+}
+
+#[derive(Deserialize)]
+pub struct AltitudeQuery {
+    #[serde(default = "default_altitude_days")]
+    days: i64,
+    #[serde(default = "default_bucket_hours")]
+    bucket_hours: i64,
+    /// Минимальный скачок высоты, считающийся манёвром подъёма, м
+    #[serde(default = "default_reboost_threshold")]
+    threshold_m: f64,
+}
+
+fn default_altitude_days() -> i64 {
+    30
+}
+
+fn default_bucket_hours() -> i64 {
+    6
+}
+
+fn default_reboost_threshold() -> f64 {
+    300.0
+}
+
+/// GET /iss/altitude/analysis?days=&bucket_hours=&threshold_m= - снижение орбиты и манёвры подъёма
+pub async fn altitude_analysis(
+    Query(query): Query<AltitudeQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<AltitudeAnalysis>>, ApiError> {
+    altitude_analysis_for(&state, ISS_NORAD_ID, query).await
+}
+
+pub(crate) async fn altitude_analysis_for(
+    state: &AppState,
+    norad_id: i32,
+    query: AltitudeQuery,
+) -> Result<Json<ApiResponse<AltitudeAnalysis>>, ApiError> {
+    let analysis = state
+        .altitude_service
+        .analyze(norad_id, query.days, query.bucket_hours, query.threshold_m)
+        .await?;
+    Ok(Json(ApiResponse::success(analysis)))
+}
+
+#[derive(Deserialize)]
+pub struct TrackExportQuery {
+    /// stored (iss_fetch_log, по умолчанию) или propagated (SGP4)
+    source: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    step: Option<i64>,
+}
+
+impl TrackExportQuery {
+    fn source(&self) -> Result<TrackSource, ApiError> {
+        match self.source.as_deref() {
+            None | Some("") => Ok(TrackSource::Stored),
+            Some(s) => TrackSource::parse(s).ok_or_else(|| {
+                ApiError::ValidationError("'source' must be 'stored' or 'propagated'".to_string())
+            }),
+        }
+    }
+}
+
+/// GET /iss/track.geojson?source=&from=&to=&step= - трасса МКС в GeoJSON
+pub async fn export_track_geojson(
+    Query(query): Query<TrackExportQuery>,
+    State(state): State<AppState>,
+) -> Result<Response, ApiError> {
+    track_geojson_for(&state, ISS_NORAD_ID, "ISS", query).await
+}
+
+/// `label` - имя объекта в названии трассы
+pub(crate) async fn track_geojson_for(
+    state: &AppState,
+    norad_id: i32,
+    label: &str,
+    query: TrackExportQuery,
+) -> Result<Response, ApiError> {
+    let source = query.source()?;
+    let from = parse_datetime_opt("from", query.from.as_deref())?;
+    let to = parse_datetime_opt("to", query.to.as_deref())?;
+    let segments = state.iss_service.ground_track(norad_id, source, from, to, query.step).await?;
+
+    let body = ground_track::to_geojson(
+        &segments,
+        serde_json::json!({
+            "name": format!("{} ground track", label),
+            "norad_id": norad_id,
+            "source": source.as_str(),
+        }),
+    );
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/geo+json")],
+        body.to_string(),
+    )
+        .into_response())
+}
+
+/// GET /iss/track.kml?source=&from=&to=&step= - трасса МКС в KML
+pub async fn export_track_kml(
+    Query(query): Query<TrackExportQuery>,
+    State(state): State<AppState>,
+) -> Result<Response, ApiError> {
+    track_kml_for(&state, ISS_NORAD_ID, "ISS", query).await
+}
+
+pub(crate) async fn track_kml_for(
+    state: &AppState,
+    norad_id: i32,
+    label: &str,
+    query: TrackExportQuery,
+) -> Result<Response, ApiError> {
+    let source = query.source()?;
+    let from = parse_datetime_opt("from", query.from.as_deref())?;
+    let to = parse_datetime_opt("to", query.to.as_deref())?;
+    let segments = state.iss_service.ground_track(norad_id, source, from, to, query.step).await?;
+
+    let body = ground_track::to_kml(&segments, &format!("{} ground track ({})", label, source.as_str()));
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/vnd.google-earth.kml+xml")],
+        body,
+    )
+        .into_response())
+}
@@ -1,7 +1,42 @@
 // Handlers layer - обработчики HTTP запросов
+pub mod admin_handlers;
+pub mod geofence_handlers;
 pub mod health;
 pub mod iss_handlers;
 pub mod osdr_handlers;
+pub mod satellite_handlers;
+pub mod solar_handlers;
 pub mod space_handlers;
+pub mod stream_handlers;
 
 pub use health::health_check;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use crate::domain::ApiError;
+
+/// Разбор даты из query-параметра: RFC 3339, "YYYY-MM-DD HH:MM:SS" или "YYYY-MM-DD" (UTC)
+pub(crate) fn parse_datetime(name: &str, value: &str) -> Result<DateTime<Utc>, ApiError> {
+    let value = value.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    for fmt in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"] {
+        if let Ok(ndt) = NaiveDateTime::parse_from_str(value, fmt) {
+            return Ok(Utc.from_utc_datetime(&ndt));
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(Utc.from_utc_datetime(&date.and_time(chrono::NaiveTime::MIN)));
+    }
+    Err(ApiError::ValidationError(format!(
+        "'{}' must be a valid date (RFC 3339 or YYYY-MM-DD)", name
+    )))
+}
+
+/// Разбор необязательного параметра-даты
+pub(crate) fn parse_datetime_opt(name: &str, value: Option<&str>) -> Result<Option<DateTime<Utc>>, ApiError> {
+    match value {
+        Some(v) if !v.trim().is_empty() => parse_datetime(name, v).map(Some),
+        _ => Ok(None),
+    }
+}
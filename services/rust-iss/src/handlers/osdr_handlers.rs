@@ -1,42 +1,352 @@
-use axum::{extract::State, Json};
-use serde::Serialize;
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use std::{io, time::Duration};
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
 use crate::{
-    domain::{ApiResponse, ApiError, osdr::OsdrItem},
+    domain::{ApiResponse, ApiError, osdr::{
+        OsdrChangeKind, OsdrChanges, OsdrDataset, OsdrExportFormat, OsdrFacetFilter, OsdrFileList, OsdrFilter,
+        OsdrHistory, OsdrListResponse, OsdrQuarantinePage, OsdrSearchResponse, OsdrSort, OsdrStats,
+        OsdrSyncReport, SortOrder,
+    }},
+    domain::sync_run::{RunSummary, RunTrigger, JOB_OSDR_SYNC},
+    handlers::parse_datetime_opt,
     AppState,
 };
 
-#[derive(Serialize)]
-struct SyncResponse {
-    written: usize,
-}
-
-/// GET /osdr/sync - синхронизация данных OSDR
+/// GET /osdr/sync - синхронизация данных OSDR, ответ - отчёт (inserted/updated/unchanged/failed)
 pub async fn sync_osdr(
     State(state): State<AppState>,
-) -> Result<Json<ApiResponse<SyncResponse>>, ApiError> {
-    let written = state.osdr_service.sync().await?;
-    Ok(Json(ApiResponse::success(SyncResponse { written })))
+) -> Result<Json<ApiResponse<OsdrSyncReport>>, ApiError> {
+    let report = state
+        .sync_run_service
+        .track(JOB_OSDR_SYNC, RunTrigger::Manual, state.osdr_service.sync(), RunSummary::from_osdr)
+        .await?;
+    Ok(Json(ApiResponse::success(report)))
+}
+
+#[derive(Deserialize)]
+pub struct ListQuery {
+    #[serde(default = "default_page")]
+    page: i64,
+    // limit - прежнее имя параметра (OsdrListRequest)
+    #[serde(alias = "limit")]
+    per_page: Option<i64>,
+    sort: Option<String>,
+    order: Option<String>,
+    status: Option<String>,
+    updated_since: Option<String>,
 }
 
-#[derive(Serialize)]
-struct OsdrListResponse {
-    items: Vec<OsdrItem>,
-    total: usize,
+fn default_page() -> i64 {
+    1
 }
 
-/// GET /osdr/list - получить список элементов OSDR
+/// GET /osdr/list?page=&per_page=&sort=&order=&status=&updated_since= - страница списка OSDR
+/// sort: inserted_at (по умолчанию), updated_at, title, dataset_id; order: asc | desc (по умолчанию)
 pub async fn list_osdr(
+    Query(query): Query<ListQuery>,
     State(state): State<AppState>,
 ) -> Result<Json<ApiResponse<OsdrListResponse>>, ApiError> {
-    let limit = std::env::var("OSDR_LIST_LIMIT")
-        .ok()
-        .and_then(|s| s.parse::<i64>().ok())
-        .unwrap_or(20);
+    let (filter, sort, order) = parse_list_params(
+        query.sort.as_deref(),
+        query.order.as_deref(),
+        query.status,
+        query.updated_since.as_deref(),
+    )?;
+
+    let response = state
+        .osdr_service
+        .list(filter, sort, order, query.page, query.per_page)
+        .await?;
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Фильтр и сортировка списка (общие для /osdr/list и /osdr/export)
+fn parse_list_params(
+    sort: Option<&str>,
+    order: Option<&str>,
+    status: Option<String>,
+    updated_since: Option<&str>,
+) -> Result<(OsdrFilter, OsdrSort, SortOrder), ApiError> {
+    let sort = match sort {
+        None | Some("") => OsdrSort::InsertedAt,
+        Some(s) => OsdrSort::parse(s).ok_or_else(|| {
+            ApiError::ValidationError(
+                "'sort' must be one of: inserted_at, updated_at, title, dataset_id".to_string(),
+            )
+        })?,
+    };
+    let order = match order {
+        None | Some("") => SortOrder::Desc,
+        Some(s) => SortOrder::parse(s)
+            .ok_or_else(|| ApiError::ValidationError("'order' must be 'asc' or 'desc'".to_string()))?,
+    };
+    let filter = OsdrFilter {
+        status: status.filter(|s| !s.trim().is_empty()),
+        updated_since: parse_datetime_opt("updated_since", updated_since)?,
+    };
+
+    Ok((filter, sort, order))
+}
+
+#[derive(Deserialize)]
+pub struct ExportQuery {
+    format: Option<String>,
+    sort: Option<String>,
+    order: Option<String>,
+    status: Option<String>,
+    updated_since: Option<String>,
+}
+
+/// Частей ответа выгрузки в очереди к клиенту
+const EXPORT_CHUNK_BUFFER: usize = 8;
+
+/// GET /osdr/export?format=csv|xlsx|jsonl&sort=&order=&status=&updated_since= - выгрузка списка OSDR
+/// файлом; фильтры и сортировка - как у /osdr/list, format по умолчанию csv.
+/// Ошибки до начала выгрузки идут с HTTP-статусом (см. file_error_response), чтобы клиент
+/// не сохранил JSON ошибки под именем файла.
+pub async fn export_osdr(
+    Query(query): Query<ExportQuery>,
+    State(state): State<AppState>,
+) -> Response {
+    start_export(query, state).await.unwrap_or_else(file_error_response)
+}
+
+/// Ошибка файлового ответа: JSON ApiResponse с HTTP-статусом вместо обычного 200
+fn file_error_response(e: ApiError) -> Response {
+    let status = match e {
+        ApiError::ValidationError(_) => StatusCode::BAD_REQUEST,
+        ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+        ApiError::UpstreamError { .. } => StatusCode::BAD_GATEWAY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, e.into_response()).into_response()
+}
+
+async fn start_export(query: ExportQuery, state: AppState) -> Result<Response, ApiError> {
+    let format = match query.format.as_deref() {
+        None | Some("") => OsdrExportFormat::Csv,
+        Some(s) => OsdrExportFormat::parse(s)
+            .ok_or_else(|| ApiError::ValidationError("'format' must be one of: csv, xlsx, jsonl".to_string()))?,
+    };
+    let (filter, sort, order) = parse_list_params(
+        query.sort.as_deref(),
+        query.order.as_deref(),
+        query.status,
+        query.updated_since.as_deref(),
+    )?;
+
+    state.osdr_service.check_export(&filter, format).await?;
+
+    let (tx, rx) = mpsc::channel(EXPORT_CHUNK_BUFFER);
+    let service = state.osdr_service.clone();
+    tokio::spawn(async move { service.export(filter, sort, order, format, tx).await });
+
+    let filename = format!("osdr_{}.{}", Utc::now().format("%Y%m%d_%H%M%S"), format.extension());
+    Response::builder()
+        .header(header::CONTENT_TYPE, format.content_type())
+        .header(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename))
+        .body(Body::from_stream(ReceiverStream::new(rx)))
+        .map_err(|e| ApiError::InternalError(format!("Failed to build export response: {}", e)))
+}
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    #[serde(default)]
+    q: String,
+    #[serde(default = "default_page")]
+    page: i64,
+    #[serde(default = "default_search_per_page")]
+    per_page: i64,
+    organism: Option<String>,
+    assay_type: Option<String>,
+    factor: Option<String>,
+}
+
+fn default_search_per_page() -> i64 {
+    20
+}
+
+/// GET /osdr/search?q=&page=&per_page=&organism=&assay_type=&factor= - полнотекстовый поиск OSDR
+pub async fn search_osdr(
+    Query(query): Query<SearchQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<OsdrSearchResponse>>, ApiError> {
+    let non_empty = |v: Option<String>| v.filter(|s| !s.trim().is_empty());
+    let facets = OsdrFacetFilter {
+        organism: non_empty(query.organism),
+        assay_type: non_empty(query.assay_type),
+        factor: non_empty(query.factor),
+    };
+
+    let response = state
+        .osdr_service
+        .search(&query.q, facets, query.page, query.per_page)
+        .await?;
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// GET /osdr/stats - распределения по статусу, организму, типу assay, миссии и месяцу обновления,
+/// самый новый и самый старый датасет
+pub async fn get_stats(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<OsdrStats>>, ApiError> {
+    let stats = state.osdr_service.stats().await?;
+    Ok(Json(ApiResponse::success(stats)))
+}
+
+#[derive(Deserialize)]
+pub struct QuarantineQuery {
+    dataset_id: Option<String>,
+    #[serde(default = "default_page")]
+    page: i64,
+    #[serde(default = "default_quarantine_per_page")]
+    per_page: i64,
+}
+
+fn default_quarantine_per_page() -> i64 {
+    50
+}
+
+/// GET /osdr/quarantine?dataset_id=&page=&per_page= - документы каталога, не прошедшие нормализацию,
+/// с ошибками по полям
+pub async fn list_quarantine(
+    Query(query): Query<QuarantineQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<OsdrQuarantinePage>>, ApiError> {
+    let dataset_id = query.dataset_id.filter(|s| !s.trim().is_empty());
+    let page = state
+        .osdr_service
+        .quarantine(dataset_id.as_deref(), query.page, query.per_page)
+        .await?;
+
+    Ok(Json(ApiResponse::success(page)))
+}
+
+/// GET /osdr/:dataset_id - датасет с нормализованными метаданными и исходным документом
+pub async fn get_dataset(
+    Path(dataset_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<OsdrDataset>>, ApiError> {
+    let dataset = state.osdr_service.get_dataset(&dataset_id).await?;
+    Ok(Json(ApiResponse::success(dataset)))
+}
+
+/// GET /osdr/:dataset_id/history - версии датасета с отличиями от предыдущей
+pub async fn get_history(
+    Path(dataset_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<OsdrHistory>>, ApiError> {
+    let history = state.osdr_service.history(&dataset_id).await?;
+    Ok(Json(ApiResponse::success(history)))
+}
+
+#[derive(Deserialize)]
+pub struct ChangesQuery {
+    since: Option<String>,
+    change: Option<String>,
+    #[serde(default = "default_changes_limit")]
+    limit: i64,
+}
+
+fn default_changes_limit() -> i64 {
+    100
+}
+
+/// GET /osdr/changes?since=&change=created|updated&limit= - новые и изменённые датасеты
+pub async fn get_changes(
+    Query(query): Query<ChangesQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<OsdrChanges>>, ApiError> {
+    let since = parse_datetime_opt("since", query.since.as_deref())?;
+    let change = match query.change.as_deref() {
+        None | Some("") => None,
+        Some(s) => Some(OsdrChangeKind::parse(s).ok_or_else(|| {
+            ApiError::ValidationError("'change' must be 'created' or 'updated'".to_string())
+        })?),
+    };
+
+    let changes = state.osdr_service.changes(since, change, query.limit).await?;
+    Ok(Json(ApiResponse::success(changes)))
+}
+
+/// GET /osdr/:dataset_id/files - манифест файлов датасета
+pub async fn list_files(
+    Path(dataset_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<OsdrFileList>>, ApiError> {
+    let files = state.osdr_service.files(&dataset_id).await?;
+    Ok(Json(ApiResponse::success(files)))
+}
+
+/// Заголовки ответа NASA, которые передаются клиенту при скачивании
+const PROXIED_HEADERS: [header::HeaderName; 6] = [
+    header::CONTENT_TYPE,
+    header::CONTENT_LENGTH,
+    header::CONTENT_RANGE,
+    header::ACCEPT_RANGES,
+    header::ETAG,
+    header::LAST_MODIFIED,
+];
+
+/// Сколько ждать следующую часть тела файла от NASA, прежде чем оборвать скачивание
+const DOWNLOAD_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// GET /osdr/:dataset_id/files/:file_id - скачивание файла через прокси, потоком.
+/// Заголовок Range передаётся NASA, ответ (200, 206 или 416) - клиенту. Ошибки до начала
+/// передачи - с HTTP-статусом (404 - нет файла, 502 - ошибка NASA).
+pub async fn download_file(
+    Path((dataset_id, file_id)): Path<(String, i64)>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Response {
+    start_download(&dataset_id, file_id, &headers, &state).await.unwrap_or_else(file_error_response)
+}
+
+async fn start_download(
+    dataset_id: &str,
+    file_id: i64,
+    headers: &HeaderMap,
+    state: &AppState,
+) -> Result<Response, ApiError> {
+    let range = headers.get(header::RANGE).and_then(|v| v.to_str().ok());
+    let (file, upstream) = state.osdr_service.download(dataset_id, file_id, range).await?;
+
+    // reqwest и axum используют разные версии http - заголовки переносятся как байты
+    let status = StatusCode::from_u16(upstream.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+    let mut response = Response::builder().status(status);
+    for name in PROXIED_HEADERS {
+        if let Some(value) = upstream.headers().get(name.as_str()) {
+            if let Ok(value) = HeaderValue::from_bytes(value.as_bytes()) {
+                response = response.header(name, value);
+            }
+        }
+    }
+    let filename: String = file
+        .name
+        .chars()
+        .map(|c| if (c.is_ascii_graphic() || c == ' ') && c != '"' && c != '\\' { c } else { '_' })
+        .collect();
+    response = response.header(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename));
 
-    let response = state.osdr_service.list(limit, 0).await?;
+    // Зависшая на середине тела передача обрывается, а не держит соединение бесконечно
+    let body = upstream.bytes_stream().timeout(DOWNLOAD_IDLE_TIMEOUT).map(|chunk| match chunk {
+        Ok(Ok(bytes)) => Ok(bytes),
+        Ok(Err(e)) => Err(io::Error::other(e)),
+        Err(_) => Err(io::Error::new(io::ErrorKind::TimedOut, "OSDR download stalled")),
+    });
 
-    Ok(Json(ApiResponse::success(OsdrListResponse {
-        items: response.items,
-        total: response.total,
-    })))
+    response
+        .body(Body::from_stream(body))
+        .map_err(|e| ApiError::InternalError(format!("Failed to build download response: {}", e)))
 }
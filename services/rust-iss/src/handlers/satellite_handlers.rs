@@ -0,0 +1,173 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    response::Response,
+    Json,
+};
+use crate::{
+    domain::{
+        ApiResponse, ApiError,
+        iss::altitude::AltitudeAnalysis,
+        iss::{IssHistory, IssIllumination, IssPasses, IssPrediction, IssRange, IssTrack, IssTrend},
+        satellite::{TrackedObject, TrackedObjectInput},
+    },
+    handlers::iss_handlers::{self, *},
+    AppState,
+};
+
+/// GET /sat - реестр отслеживаемых объектов
+pub async fn list_objects(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<TrackedObject>>>, ApiError> {
+    let objects = state.iss_service.list_objects(false).await?;
+    Ok(Json(ApiResponse::success(objects)))
+}
+
+/// POST /sat - добавить объект или обновить существующий
+/// {"norad_id": 48274, "name": "CSS (TIANHE)", "poll_every_seconds": 300, "enabled": true}
+pub async fn upsert_object(
+    State(state): State<AppState>,
+    body: Bytes,
+) -> Result<Json<ApiResponse<TrackedObject>>, ApiError> {
+    let input: TrackedObjectInput = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::ValidationError(format!("invalid tracked object body: {}", e)))?;
+    let object = state.iss_service.upsert_object(input).await?;
+    Ok(Json(ApiResponse::success(object)))
+}
+
+/// GET /sat/:norad - объект из реестра
+pub async fn get_object(
+    Path(norad): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<TrackedObject>>, ApiError> {
+    let object = state.iss_service.tracked_object(norad).await?;
+    Ok(Json(ApiResponse::success(object)))
+}
+
+/// DELETE /sat/:norad - перестать отслеживать объект
+pub async fn delete_object(
+    Path(norad): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    state.iss_service.delete_object(norad).await?;
+    Ok(Json(ApiResponse::success(serde_json::json!({ "deleted": norad }))))
+}
+
+/// GET /sat/:norad/last - как /last, для объекта из реестра
+pub async fn get_last(
+    Path(norad): Path<i32>,
+    Query(query): Query<LastQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    state.iss_service.tracked_object(norad).await?;
+    iss_handlers::last_for(&state, norad, query).await
+}
+
+/// GET /sat/:norad/history - как /iss/history
+pub async fn get_history(
+    Path(norad): Path<i32>,
+    Query(query): Query<HistoryQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<IssHistory>>, ApiError> {
+    state.iss_service.tracked_object(norad).await?;
+    history_for(&state, norad, query).await
+}
+
+/// GET /sat/:norad/fetch - как /fetch
+pub async fn trigger_fetch(
+    Path(norad): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    state.iss_service.tracked_object(norad).await?;
+    fetch_for(&state, norad).await
+}
+
+/// GET /sat/:norad/trend - как /iss/trend
+pub async fn get_trend(
+    Path(norad): Path<i32>,
+    Query(query): Query<TrendQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<IssTrend>>, ApiError> {
+    state.iss_service.tracked_object(norad).await?;
+    trend_for(&state, norad, query).await
+}
+
+/// GET /sat/:norad/range - как /iss/range
+pub async fn get_range(
+    Path(norad): Path<i32>,
+    Query(query): Query<RangeQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<IssRange>>, ApiError> {
+    state.iss_service.tracked_object(norad).await?;
+    range_for(&state, norad, query).await
+}
+
+/// GET /sat/:norad/predict - как /iss/predict
+pub async fn predict(
+    Path(norad): Path<i32>,
+    Query(query): Query<PredictQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<IssPrediction>>, ApiError> {
+    state.iss_service.tracked_object(norad).await?;
+    predict_for(&state, norad, query).await
+}
+
+/// GET /sat/:norad/track - как /iss/track
+pub async fn get_track(
+    Path(norad): Path<i32>,
+    Query(query): Query<TrackQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<IssTrack>>, ApiError> {
+    state.iss_service.tracked_object(norad).await?;
+    track_for(&state, norad, query).await
+}
+
+/// GET /sat/:norad/passes - как /iss/passes
+pub async fn get_passes(
+    Path(norad): Path<i32>,
+    Query(query): Query<PassesQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<IssPasses>>, ApiError> {
+    state.iss_service.tracked_object(norad).await?;
+    passes_for(&state, norad, query).await
+}
+
+/// GET /sat/:norad/illumination - как /iss/illumination
+pub async fn get_illumination(
+    Path(norad): Path<i32>,
+    Query(query): Query<IlluminationQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<IssIllumination>>, ApiError> {
+    state.iss_service.tracked_object(norad).await?;
+    illumination_for(&state, norad, query).await
+}
+
+/// GET /sat/:norad/altitude/analysis - как /iss/altitude/analysis
+pub async fn altitude_analysis(
+    Path(norad): Path<i32>,
+    Query(query): Query<AltitudeQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<AltitudeAnalysis>>, ApiError> {
+    state.iss_service.tracked_object(norad).await?;
+    altitude_analysis_for(&state, norad, query).await
+}
+
+/// GET /sat/:norad/track.geojson - как /iss/track.geojson
+pub async fn export_track_geojson(
+    Path(norad): Path<i32>,
+    Query(query): Query<TrackExportQuery>,
+    State(state): State<AppState>,
+) -> Result<Response, ApiError> {
+    let object = state.iss_service.tracked_object(norad).await?;
+    track_geojson_for(&state, norad, &object.name, query).await
+}
+
+/// GET /sat/:norad/track.kml - как /iss/track.kml
+pub async fn export_track_kml(
+    Path(norad): Path<i32>,
+    Query(query): Query<TrackExportQuery>,
+    State(state): State<AppState>,
+) -> Result<Response, ApiError> {
+    let object = state.iss_service.tracked_object(norad).await?;
+    track_kml_for(&state, norad, &object.name, query).await
+}
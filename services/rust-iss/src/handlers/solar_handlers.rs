@@ -0,0 +1,38 @@
+use axum::{
+    extract::Query,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use crate::{
+    domain::{solar, ApiError},
+    handlers::parse_datetime_opt,
+};
+
+#[derive(Deserialize)]
+pub struct TerminatorQuery {
+    at: Option<String>,
+    /// Шаг по долготе, градусы
+    #[serde(default = "default_terminator_step")]
+    step: f64,
+}
+
+fn default_terminator_step() -> f64 {
+    1.0
+}
+
+/// GET /solar/terminator?at=&step= - ночное полушарие, линия терминатора и подсолнечная точка в GeoJSON
+pub async fn get_terminator(Query(query): Query<TerminatorQuery>) -> Result<Response, ApiError> {
+    if !(0.1..=10.0).contains(&query.step) {
+        return Err(ApiError::ValidationError("'step' must be between 0.1 and 10 degrees".to_string()));
+    }
+    let at = parse_datetime_opt("at", query.at.as_deref())?.unwrap_or_else(chrono::Utc::now);
+
+    let body = solar::terminator_geojson(at, query.step);
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/geo+json")],
+        body.to_string(),
+    )
+        .into_response())
+}
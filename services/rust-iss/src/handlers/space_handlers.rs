@@ -6,7 +6,11 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use crate::{
-    domain::{ApiResponse, ApiError},
+    domain::{
+        ApiResponse, ApiError,
+        satellite::ISS_NORAD_ID,
+        sync_run::{RunSummary, RunTrigger, JOB_SPACE_REFRESH},
+    },
     AppState,
 };
 
@@ -59,7 +63,20 @@ pub async fn refresh_sources(
     State(state): State<AppState>,
 ) -> Result<Json<ApiResponse<RefreshResponse>>, ApiError> {
     let sources: Vec<&str> = query.src.split(',').map(|s| s.trim()).collect();
-    let refreshed = state.space_service.refresh_multiple(&sources).await?;
+    let refreshed = state
+        .sync_run_service
+        .track(
+            JOB_SPACE_REFRESH,
+            RunTrigger::Manual,
+            state.space_service.refresh_multiple(&sources),
+            |refreshed| RunSummary {
+                items: refreshed.len() as i64,
+                failed: sources.len().saturating_sub(refreshed.len()) as i64,
+                details: Some(serde_json::json!({ "requested": sources, "refreshed": refreshed })),
+                error: None,
+            },
+        )
+        .await?;
 
     Ok(Json(ApiResponse::success(RefreshResponse { refreshed })))
 }
@@ -72,7 +89,7 @@ pub async fn get_summary(
     let mut summary = state.space_service.get_summary(osdr_count).await?;
 
     // Добавляем данные ISS
-    if let Ok(Some(iss_pos)) = state.iss_service.get_last_position().await {
+    if let Ok(Some(iss_pos)) = state.iss_service.get_last_position(ISS_NORAD_ID).await {
         summary.iss = Some(serde_json::json!({
             "at": iss_pos.fetched_at,
             "payload": iss_pos.payload,
@@ -0,0 +1,139 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Response,
+    },
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::{convert::Infallible, time::Duration};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_stream::{wrappers::{errors::BroadcastStreamRecvError, BroadcastStream}, Stream, StreamExt};
+use tracing::{debug, warn};
+use crate::{domain::{satellite::ISS_NORAD_ID, ApiError}, AppState};
+
+/// Интервал heartbeat для SSE-комментариев и WebSocket ping
+const HEARTBEAT: Duration = Duration::from_secs(15);
+/// Клиент, не принявший сообщение за это время, отключается
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize)]
+pub struct StreamQuery {
+    /// NORAD ID объекта (по умолчанию - МКС)
+    norad: Option<i32>,
+}
+
+/// GET /iss/stream?norad= - новые позиции МКС через Server-Sent Events
+/// События: `position` (IssPosition), `lagged` (клиент не успевал, пропущено N позиций)
+pub async fn iss_sse(
+    Query(query): Query<StreamQuery>,
+    State(state): State<AppState>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let norad_id = stream_norad(&state, &query).await?;
+    Ok(sse_for(&state, norad_id))
+}
+
+/// GET /sat/:norad/stream - как /iss/stream, для объекта из реестра
+pub async fn sat_sse(
+    Path(norad): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    state.iss_service.tracked_object(norad).await?;
+    Ok(sse_for(&state, norad))
+}
+
+fn sse_for(state: &AppState, norad_id: i32) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    // Канал общий для всех объектов; lagged считается по всему каналу
+    let stream = BroadcastStream::new(state.iss_service.subscribe()).filter_map(move |msg| {
+        let event = match msg {
+            Ok(position) if position.norad_id != norad_id => return None,
+            Ok(position) => Event::default()
+                .event("position")
+                .json_data(&position)
+                .unwrap_or_else(|_| Event::default().event("error").data("serialization failed")),
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => Event::default()
+                .event("lagged")
+                .data(json!({ "skipped": skipped }).to_string()),
+        };
+        Some(Ok(event))
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::new().interval(HEARTBEAT).text("heartbeat"))
+}
+
+/// GET /ws/iss?norad= - новые позиции МКС через WebSocket
+/// Сообщения: {"type":"position","data":{...}} и {"type":"lagged","skipped":N}
+pub async fn iss_ws(
+    ws: WebSocketUpgrade,
+    Query(query): Query<StreamQuery>,
+    State(state): State<AppState>,
+) -> Result<Response, ApiError> {
+    let norad_id = stream_norad(&state, &query).await?;
+    Ok(ws.on_upgrade(move |socket| ws_session(socket, state, norad_id)))
+}
+
+/// Объект потока: ?norad= должен быть в реестре, иначе подписка молча ждала бы вечно
+async fn stream_norad(state: &AppState, query: &StreamQuery) -> Result<i32, ApiError> {
+    match query.norad {
+        Some(norad_id) => {
+            state.iss_service.tracked_object(norad_id).await?;
+            Ok(norad_id)
+        }
+        None => Ok(ISS_NORAD_ID),
+    }
+}
+
+async fn ws_session(mut socket: WebSocket, state: AppState, norad_id: i32) {
+    let mut positions = state.iss_service.subscribe();
+    let mut heartbeat = tokio::time::interval(HEARTBEAT);
+
+    loop {
+        let message = tokio::select! {
+            received = positions.recv() => match received {
+                Ok(position) if position.norad_id != norad_id => continue,
+                Ok(position) => json!({ "type": "position", "data": position }),
+                // Медленный клиент: часть позиций вытеснена из буфера канала
+                Err(RecvError::Lagged(skipped)) => json!({ "type": "lagged", "skipped": skipped }),
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => {
+                    debug!("WebSocket receive error: {}", e);
+                    break;
+                }
+            },
+            _ = heartbeat.tick() => {
+                if send(&mut socket, Message::Ping(Vec::new())).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        if send(&mut socket, Message::Text(message.to_string())).await.is_err() {
+            break;
+        }
+    }
+
+    let _ = socket.close().await;
+}
+
+async fn send(socket: &mut WebSocket, message: Message) -> Result<(), ()> {
+    match tokio::time::timeout(SEND_TIMEOUT, socket.send(message)).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => {
+            debug!("WebSocket send error: {}", e);
+            Err(())
+        }
+        Err(_) => {
+            warn!("WebSocket client too slow, disconnecting");
+            Err(())
+        }
+    }
+}
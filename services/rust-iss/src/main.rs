@@ -25,8 +25,11 @@ use tower_http::trace::TraceLayer;
 #[derive(Clone)]
 pub struct AppState {
     pub iss_service: Arc<IssService>,
+    pub altitude_service: Arc<AltitudeService>,
+    pub geofence_service: Arc<GeofenceService>,
     pub osdr_service: Arc<OsdrService>,
     pub space_service: Arc<SpaceService>,
+    pub sync_run_service: Arc<SyncRunService>,
 }
 
 #[tokio::main]
@@ -60,7 +63,8 @@ async fn main() -> anyhow::Result<()> {
 
     // Инициализация клиентов
     info!("🌐 Initializing API clients...");
-    let iss_client = IssClient::new(config.where_iss_url.clone())?;
+    let iss_client = IssClient::new(config.iss_tle_url.clone(), config.sat_tle_url.clone())?;
+    let iss_providers = build_iss_providers(&config, &pool)?;
     let nasa_client = NasaClient::new(config.nasa_api_key.clone())?;
     let spacex_client = SpacexClient::new()?;
 
@@ -70,11 +74,25 @@ async fn main() -> anyhow::Result<()> {
     let cache_repo = CacheRepo::new(pool.clone());
 
     // Инициализация сервисов
-    let iss_service = Arc::new(IssService::new(iss_repo, iss_client));
+    let geofence_service = Arc::new(GeofenceService::new(GeofenceRepo::new(pool.clone())));
+    let iss_service = Arc::new(IssService::new(
+        iss_repo,
+        TrackedObjectRepo::new(pool.clone()),
+        iss_client,
+        iss_providers,
+        CacheRepo::new(pool.clone()),
+        Arc::clone(&geofence_service),
+        config.iss_stream_capacity,
+    ));
+    let altitude_service = Arc::new(AltitudeService::new(IssRepo::new(pool.clone())));
     let osdr_service = Arc::new(OsdrService::new(
         osdr_repo,
         nasa_client.clone(),
         config.nasa_api_url.clone(),
+        config.osdr_files_url.clone(),
+        config.osdr_files_per_sync,
+        config.osdr_max_pages,
+        config.osdr_list_limit,
     ));
     let space_service = Arc::new(SpaceService::new(
         cache_repo,
@@ -82,11 +100,16 @@ async fn main() -> anyhow::Result<()> {
         spacex_client,
     ));
 
+    let sync_run_service = Arc::new(SyncRunService::new(SyncRunRepo::new(pool.clone())));
+
     // Создание состояния приложения
     let app_state = AppState {
         iss_service: Arc::clone(&iss_service),
+        altitude_service: Arc::clone(&altitude_service),
+        geofence_service: Arc::clone(&geofence_service),
         osdr_service: Arc::clone(&osdr_service),
         space_service: Arc::clone(&space_service),
+        sync_run_service: Arc::clone(&sync_run_service),
     };
 
     // Запуск фоновых задач
@@ -96,6 +119,7 @@ async fn main() -> anyhow::Result<()> {
         Arc::clone(&iss_service),
         Arc::clone(&osdr_service),
         Arc::clone(&space_service),
+        Arc::clone(&sync_run_service),
     );
     scheduler.start_all();
 
@@ -124,6 +148,30 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Провайдеры позиции МКС в порядке ISS_PROVIDERS
+fn build_iss_providers(
+    config: &AppConfig,
+    pool: &sqlx::PgPool,
+) -> anyhow::Result<Vec<Arc<dyn IssPositionProvider>>> {
+    let mut providers: Vec<Arc<dyn IssPositionProvider>> = Vec::new();
+    for name in &config.iss_providers {
+        match name.as_str() {
+            "wheretheiss" => providers.push(Arc::new(WhereTheIssProvider::new(config.where_iss_url.clone())?)),
+            "open_notify" => providers.push(Arc::new(OpenNotifyProvider::new(config.open_notify_url.clone())?)),
+            "tle" => providers.push(Arc::new(TlePropagationProvider::new(CacheRepo::new(pool.clone())))),
+            other => anyhow::bail!("Unknown ISS provider '{}' in ISS_PROVIDERS", other),
+        }
+    }
+    if providers.is_empty() {
+        anyhow::bail!("ISS_PROVIDERS must list at least one provider");
+    }
+    info!(
+        "🛰️  ISS providers: {}",
+        providers.iter().map(|p| p.name()).collect::<Vec<_>>().join(" -> ")
+    );
+    Ok(providers)
+}
+
 /// Инициализация схемы базы данных
 async fn init_database(pool: &sqlx::PgPool) -> anyhow::Result<()> {
     // ISS tracking
@@ -142,6 +190,103 @@ async fn init_database(pool: &sqlx::PgPool) -> anyhow::Result<()> {
         .execute(pool)
         .await?;
 
+    // Типизированные колонки позиции (заполняются при вставке и бэкфиллом)
+    sqlx::query(
+        "ALTER TABLE iss_fetch_log
+            ADD COLUMN IF NOT EXISTS latitude DOUBLE PRECISION,
+            ADD COLUMN IF NOT EXISTS longitude DOUBLE PRECISION,
+            ADD COLUMN IF NOT EXISTS altitude DOUBLE PRECISION,
+            ADD COLUMN IF NOT EXISTS velocity DOUBLE PRECISION,
+            ADD COLUMN IF NOT EXISTS visibility TEXT,
+            ADD COLUMN IF NOT EXISTS position_at TIMESTAMPTZ,
+            ADD COLUMN IF NOT EXISTS provider TEXT"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_iss_position_at ON iss_fetch_log(position_at DESC)")
+        .execute(pool)
+        .await?;
+
+    // Журнал общий для всех объектов; старые записи относятся к МКС
+    sqlx::query("ALTER TABLE iss_fetch_log ADD COLUMN IF NOT EXISTS norad_id INTEGER NOT NULL DEFAULT 25544")
+        .execute(pool)
+        .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS ix_iss_norad_fetched_at
+         ON iss_fetch_log(norad_id, fetched_at DESC)"
+    )
+    .execute(pool)
+    .await?;
+
+    // Реестр отслеживаемых объектов
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS tracked_objects(
+            norad_id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            poll_every_seconds BIGINT,
+            enabled BOOLEAN NOT NULL DEFAULT TRUE,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // МКС опрашивается как раньше; остальные - по включению
+    sqlx::query(
+        "INSERT INTO tracked_objects (norad_id, name, enabled) VALUES
+            (25544, 'ISS (ZARYA)', TRUE),
+            (48274, 'CSS (TIANHE)', FALSE),
+            (20580, 'HST', FALSE)
+         ON CONFLICT (norad_id) DO NOTHING"
+    )
+    .execute(pool)
+    .await?;
+
+    // Геозоны и события входа/выхода МКС
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS iss_geofences(
+            id BIGSERIAL PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            shape JSONB NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS iss_geofence_events(
+            id BIGSERIAL PRIMARY KEY,
+            geofence_id BIGINT NOT NULL REFERENCES iss_geofences(id) ON DELETE CASCADE,
+            geofence_name TEXT NOT NULL,
+            event_type TEXT NOT NULL,
+            occurred_at TIMESTAMPTZ NOT NULL,
+            position_id BIGINT,
+            latitude DOUBLE PRECISION NOT NULL,
+            longitude DOUBLE PRECISION NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS ix_iss_geofence_events_occurred
+         ON iss_geofence_events(occurred_at DESC)"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS ix_iss_geofence_events_geofence
+         ON iss_geofence_events(geofence_id, occurred_at DESC)"
+    )
+    .execute(pool)
+    .await?;
+
     // OSDR datasets
     sqlx::query(
         "CREATE TABLE IF NOT EXISTS osdr_items(
@@ -164,6 +309,199 @@ async fn init_database(pool: &sqlx::PgPool) -> anyhow::Result<()> {
     .execute(pool)
     .await?;
 
+    // Поля для полнотекстового поиска и фасетов (заполняются при sync)
+    sqlx::query(
+        "ALTER TABLE osdr_items
+            ADD COLUMN IF NOT EXISTS description TEXT,
+            ADD COLUMN IF NOT EXISTS organisms TEXT[] NOT NULL DEFAULT '{}',
+            ADD COLUMN IF NOT EXISTS assay_types TEXT[] NOT NULL DEFAULT '{}',
+            ADD COLUMN IF NOT EXISTS factors TEXT[] NOT NULL DEFAULT '{}',
+            ADD COLUMN IF NOT EXISTS search_vector TSVECTOR,
+            ADD COLUMN IF NOT EXISTS content_hash TEXT"
+    )
+    .execute(pool)
+    .await?;
+
+    // Записи до появления поиска: вектор по заголовку и dataset_id, остальное - при следующем sync
+    sqlx::query(
+        "UPDATE osdr_items
+         SET search_vector = setweight(to_tsvector('english', COALESCE(title, '')), 'A') ||
+                             setweight(to_tsvector('simple', COALESCE(dataset_id, '')), 'A')
+         WHERE search_vector IS NULL"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_osdr_search ON osdr_items USING GIN(search_vector)")
+        .execute(pool)
+        .await?;
+
+    // Версия правил нормализации записи (NULL - до появления версий, перезапишется при sync)
+    // и название миссии для статистики
+    sqlx::query(
+        "ALTER TABLE osdr_items
+            ADD COLUMN IF NOT EXISTS schema_version INTEGER,
+            ADD COLUMN IF NOT EXISTS mission TEXT"
+    )
+    .execute(pool)
+    .await?;
+
+    // Карантин: документы каталога, не прошедшие нормализацию
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_quarantine(
+            id BIGSERIAL PRIMARY KEY,
+            dataset_id TEXT,
+            content_hash TEXT NOT NULL UNIQUE,
+            schema_version INTEGER NOT NULL,
+            errors JSONB NOT NULL,
+            raw JSONB NOT NULL,
+            first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            occurrences INTEGER NOT NULL DEFAULT 1
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_osdr_quarantine_dataset ON osdr_quarantine(dataset_id)")
+        .execute(pool)
+        .await?;
+
+    // Манифесты файлов OSDR; files_synced_at сбрасывается при изменении датасета
+    sqlx::query("ALTER TABLE osdr_items ADD COLUMN IF NOT EXISTS files_synced_at TIMESTAMPTZ")
+        .execute(pool)
+        .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_files(
+            id BIGSERIAL PRIMARY KEY,
+            dataset_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            size_bytes BIGINT,
+            category TEXT,
+            subcategory TEXT,
+            url TEXT NOT NULL,
+            checksum TEXT,
+            fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("DROP INDEX IF EXISTS ix_osdr_files_dataset")
+        .execute(pool)
+        .await?;
+
+    // Файл адресуется по имени в датасете, чтобы id не менялся между синхронизациями.
+    // Повторы из прежних манифестов удаляются до создания уникального индекса.
+    sqlx::query(
+        "DELETE FROM osdr_files f
+         USING osdr_files d
+         WHERE f.dataset_id = d.dataset_id AND f.name = d.name AND f.id > d.id"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE UNIQUE INDEX IF NOT EXISTS ux_osdr_files_dataset_name
+         ON osdr_files(dataset_id, name)"
+    )
+    .execute(pool)
+    .await?;
+
+    // История изменений OSDR: версия на каждый новый или изменившийся документ
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_item_versions(
+            id BIGSERIAL PRIMARY KEY,
+            item_id BIGINT NOT NULL REFERENCES osdr_items(id) ON DELETE CASCADE,
+            dataset_id TEXT,
+            version INTEGER NOT NULL,
+            change TEXT NOT NULL,
+            title TEXT,
+            status TEXT,
+            updated_at TIMESTAMPTZ,
+            raw JSONB NOT NULL,
+            diff JSONB NOT NULL DEFAULT '[]',
+            recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS ix_osdr_versions_item
+         ON osdr_item_versions(item_id, version DESC)"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS ix_osdr_versions_recorded_at
+         ON osdr_item_versions(recorded_at DESC)"
+    )
+    .execute(pool)
+    .await?;
+
+    // Записи до появления истории: первая версия - текущее состояние на момент вставки
+    sqlx::query(
+        "INSERT INTO osdr_item_versions(item_id, dataset_id, version, change, title, status, updated_at, raw, recorded_at)
+         SELECT i.id, i.dataset_id, 1, 'created', i.title, i.status, i.updated_at, i.raw, i.inserted_at
+         FROM osdr_items i
+         WHERE NOT EXISTS (SELECT 1 FROM osdr_item_versions v WHERE v.item_id = i.id)"
+    )
+    .execute(pool)
+    .await?;
+
+    // Позиция обхода каталога OSDR, прерванного ошибкой или лимитом страниц (одна строка)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_sync_cursor(
+            id SMALLINT PRIMARY KEY DEFAULT 1 CHECK (id = 1),
+            start_url TEXT NOT NULL,
+            url TEXT NOT NULL,
+            page INTEGER NOT NULL,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // Журнал запусков задач загрузки (планировщик и ручные вызовы)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS sync_runs(
+            id BIGSERIAL PRIMARY KEY,
+            job TEXT NOT NULL,
+            trigger TEXT NOT NULL,
+            started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            finished_at TIMESTAMPTZ,
+            duration_ms BIGINT,
+            outcome TEXT NOT NULL,
+            items BIGINT,
+            failed_items BIGINT,
+            error_code TEXT,
+            error_message TEXT,
+            details JSONB
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS ix_sync_runs_job_started_at
+         ON sync_runs(job, started_at DESC)"
+    )
+    .execute(pool)
+    .await?;
+
+    // Запуски, прерванные остановкой процесса, остались в running
+    sqlx::query(
+        "UPDATE sync_runs
+         SET outcome = 'failed', finished_at = NOW(),
+             error_code = 'INTERRUPTED', error_message = 'Process stopped before the run finished'
+         WHERE outcome = 'running'"
+    )
+    .execute(pool)
+    .await?;
+
     // Universal space cache
     sqlx::query(
         "CREATE TABLE IF NOT EXISTS space_cache(
@@ -0,0 +1,159 @@
+use crate::domain::{geofence::*, ApiError};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+
+const GEOFENCE_COLUMNS: &str = "id, name, shape, created_at, updated_at";
+const EVENT_COLUMNS: &str =
+    "id, geofence_id, geofence_name, event_type, occurred_at, position_id, latitude, longitude";
+
+/// Репозиторий геозон и событий пересечения их границ
+pub struct GeofenceRepo {
+    pool: PgPool,
+}
+
+impl GeofenceRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn shape_json(shape: &GeofenceShape) -> Result<serde_json::Value, ApiError> {
+        serde_json::to_value(shape).map_err(|e| ApiError::InternalError(e.to_string()))
+    }
+
+    /// Ошибка уникальности имени превращается в ошибку валидации
+    fn map_write_error(err: sqlx::Error, name: &str) -> ApiError {
+        match err.as_database_error().and_then(|e| e.code()) {
+            Some(code) if code == "23505" => {
+                ApiError::ValidationError(format!("geofence '{}' already exists", name))
+            }
+            _ => ApiError::from(err),
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<Geofence>, ApiError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM iss_geofences ORDER BY id", GEOFENCE_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(Geofence::from_row)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))
+    }
+
+    pub async fn get(&self, id: i64) -> Result<Option<Geofence>, ApiError> {
+        let row_opt = sqlx::query(&format!(
+            "SELECT {} FROM iss_geofences WHERE id = $1", GEOFENCE_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row_opt {
+            Some(row) => Ok(Some(Geofence::from_row(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn insert(&self, input: &GeofenceInput) -> Result<Geofence, ApiError> {
+        let row = sqlx::query(&format!(
+            "INSERT INTO iss_geofences (name, shape) VALUES ($1, $2) RETURNING {}",
+            GEOFENCE_COLUMNS
+        ))
+        .bind(&input.name)
+        .bind(Self::shape_json(&input.shape)?)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Self::map_write_error(e, &input.name))?;
+
+        Ok(Geofence::from_row(&row)?)
+    }
+
+    pub async fn update(&self, id: i64, input: &GeofenceInput) -> Result<Option<Geofence>, ApiError> {
+        let row_opt = sqlx::query(&format!(
+            "UPDATE iss_geofences SET name = $2, shape = $3, updated_at = NOW()
+             WHERE id = $1 RETURNING {}",
+            GEOFENCE_COLUMNS
+        ))
+        .bind(id)
+        .bind(&input.name)
+        .bind(Self::shape_json(&input.shape)?)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Self::map_write_error(e, &input.name))?;
+
+        match row_opt {
+            Some(row) => Ok(Some(Geofence::from_row(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Удаление геозоны вместе с её событиями; false - геозоны не было
+    pub async fn delete(&self, id: i64) -> Result<bool, ApiError> {
+        let result = sqlx::query("DELETE FROM iss_geofences WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn insert_event(
+        &self,
+        geofence: &Geofence,
+        transition: GeofenceTransition,
+        occurred_at: DateTime<Utc>,
+        position_id: i64,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<i64, ApiError> {
+        let row = sqlx::query(
+            "INSERT INTO iss_geofence_events
+                (geofence_id, geofence_name, event_type, occurred_at, position_id, latitude, longitude)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING id"
+        )
+        .bind(geofence.id)
+        .bind(&geofence.name)
+        .bind(transition.as_str())
+        .bind(occurred_at)
+        .bind(position_id)
+        .bind(latitude)
+        .bind(longitude)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get("id"))
+    }
+
+    /// События за период (от новых к старым), опционально по одной геозоне
+    pub async fn list_events(
+        &self,
+        geofence_id: Option<i64>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<GeofenceEvent>, ApiError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM iss_geofence_events
+             WHERE ($1::BIGINT IS NULL OR geofence_id = $1)
+               AND occurred_at >= $2 AND occurred_at <= $3
+             ORDER BY occurred_at DESC, id DESC
+             LIMIT $4",
+            EVENT_COLUMNS
+        ))
+        .bind(geofence_id)
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(GeofenceEvent::from_row)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))
+    }
+}
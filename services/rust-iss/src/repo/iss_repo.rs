@@ -1,4 +1,4 @@
-use crate::domain::{iss::*, ApiError};
+use crate::domain::{iss::{altitude::AltitudeBucket, *}, ApiError};
 use chrono::{DateTime, Utc};
 use serde_json::Value;
 use sqlx::{PgPool, Row};
@@ -15,11 +15,29 @@ impl IssRepo {
     }
 
     /// Вставка новой записи о позиции МКС
-    pub async fn insert(&self, source_url: &str, payload: Value) -> Result<i64, ApiError> {
+    /// Типизированные колонки заполняются из payload, чтобы фильтровать и агрегировать в SQL
+    pub async fn insert(
+        &self,
+        norad_id: i32,
+        source_url: &str,
+        provider: &str,
+        payload: Value,
+    ) -> Result<i64, ApiError> {
         let row = sqlx::query(
-            "INSERT INTO iss_fetch_log (source_url, payload) VALUES ($1, $2) RETURNING id"
+            "INSERT INTO iss_fetch_log
+                (norad_id, source_url, provider, latitude, longitude, altitude, velocity, visibility, position_at, payload)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+             RETURNING id"
         )
+        .bind(norad_id)
         .bind(source_url)
+        .bind(provider)
+        .bind(IssPosition::extract_number(&payload, "latitude"))
+        .bind(IssPosition::extract_number(&payload, "longitude"))
+        .bind(IssPosition::extract_number(&payload, "altitude"))
+        .bind(IssPosition::extract_number(&payload, "velocity"))
+        .bind(IssPosition::extract_string(&payload, "visibility"))
+        .bind(IssPosition::extract_timestamp(&payload, "timestamp"))
         .bind(payload)
         .fetch_one(&self.pool)
         .await?;
@@ -27,13 +45,68 @@ impl IssRepo {
         Ok(row.get("id"))
     }
 
-    /// Получить последнюю запись
-    pub async fn get_last(&self) -> Result<Option<IssPosition>, ApiError> {
+    /// Заполнить типизированные колонки у старых записей (только JSONB payload)
+    /// Обрабатывает одну пачку с id > `after_id`; возвращает id обработанных записей
+    pub async fn backfill_typed_columns(&self, after_id: i64, batch: i64) -> Result<Vec<i64>, ApiError> {
+        let rows = sqlx::query(
+            "UPDATE iss_fetch_log t SET
+                latitude = CASE WHEN t.payload->>'latitude' ~ $3
+                    THEN (t.payload->>'latitude')::DOUBLE PRECISION END,
+                longitude = CASE WHEN t.payload->>'longitude' ~ $3
+                    THEN (t.payload->>'longitude')::DOUBLE PRECISION END,
+                altitude = CASE WHEN t.payload->>'altitude' ~ $3
+                    THEN (t.payload->>'altitude')::DOUBLE PRECISION END,
+                velocity = CASE WHEN t.payload->>'velocity' ~ $3
+                    THEN (t.payload->>'velocity')::DOUBLE PRECISION END,
+                visibility = NULLIF(t.payload->>'visibility', ''),
+                position_at = CASE WHEN t.payload->>'timestamp' ~ '^[0-9]{1,12}$'
+                    THEN to_timestamp((t.payload->>'timestamp')::BIGINT) END
+             FROM (
+                 SELECT id FROM iss_fetch_log
+                 WHERE id > $1 AND latitude IS NULL
+                 ORDER BY id
+                 LIMIT $2
+             ) b
+             WHERE t.id = b.id
+             RETURNING t.id"
+        )
+        .bind(after_id)
+        .bind(batch)
+        .bind(r"^\s*-?[0-9]+(\.[0-9]+)?([eE][-+]?[0-9]+)?\s*$")
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| row.get("id")).collect())
+    }
+
+    /// Получить последнюю запись объекта
+    pub async fn get_last(&self, norad_id: i32) -> Result<Option<IssPosition>, ApiError> {
         let row_opt = sqlx::query(
-            "SELECT id, fetched_at, source_url, payload
+            "SELECT id, fetched_at, source_url, payload,
+                    norad_id, provider, latitude, longitude, altitude, velocity, visibility, position_at
              FROM iss_fetch_log
+             WHERE norad_id = $1
              ORDER BY id DESC LIMIT 1"
         )
+        .bind(norad_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row_opt {
+            Some(row) => Ok(Some(IssPosition::from_row(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Получить запись по id
+    pub async fn get_by_id(&self, id: i64) -> Result<Option<IssPosition>, ApiError> {
+        let row_opt = sqlx::query(
+            "SELECT id, fetched_at, source_url, payload,
+                    norad_id, provider, latitude, longitude, altitude, velocity, visibility, position_at
+             FROM iss_fetch_log
+             WHERE id = $1"
+        )
+        .bind(id)
         .fetch_optional(&self.pool)
         .await?;
 
@@ -43,14 +116,23 @@ impl IssRepo {
         }
     }
 
-    /// Получить N последних записей для анализа тренда
-    pub async fn get_last_n(&self, n: i64) -> Result<Vec<IssPosition>, ApiError> {
+    /// Получить N последних записей (курсор `before_id` - для постраничного чтения истории)
+    pub async fn get_last_n(
+        &self,
+        norad_id: i32,
+        n: i64,
+        before_id: Option<i64>,
+    ) -> Result<Vec<IssPosition>, ApiError> {
         let rows = sqlx::query(
-            "SELECT id, fetched_at, source_url, payload
+            "SELECT id, fetched_at, source_url, payload,
+                    norad_id, provider, latitude, longitude, altitude, velocity, visibility, position_at
              FROM iss_fetch_log
+             WHERE norad_id = $3 AND ($2::BIGINT IS NULL OR id < $2)
              ORDER BY id DESC LIMIT $1"
         )
         .bind(n)
+        .bind(before_id)
+        .bind(norad_id)
         .fetch_all(&self.pool)
         .await?;
 
@@ -60,26 +142,174 @@ impl IssRepo {
             .map_err(|e| ApiError::DatabaseError(e.to_string()))
     }
 
-    /// Получить записи за период
+    /// Получить записи за период (по возрастанию времени)
+    /// `step_sec` - прореживание: не более одной записи на интервал в N секунд
     pub async fn get_by_time_range(
         &self,
+        norad_id: i32,
         from: DateTime<Utc>,
         to: DateTime<Utc>,
+        step_sec: Option<i64>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<IssPosition>, ApiError> {
+        let rows = match step_sec {
+            Some(step) => {
+                sqlx::query(
+                    "SELECT id, fetched_at, source_url, payload,
+                            norad_id, provider, latitude, longitude, altitude, velocity, visibility, position_at
+                     FROM (
+                         SELECT DISTINCT ON (floor(extract(epoch FROM fetched_at) / $3))
+                                id, fetched_at, source_url, payload,
+                                norad_id, provider, latitude, longitude, altitude, velocity, visibility, position_at
+                         FROM iss_fetch_log
+                         WHERE norad_id = $6 AND fetched_at BETWEEN $1 AND $2
+                         ORDER BY floor(extract(epoch FROM fetched_at) / $3), fetched_at
+                     ) s
+                     ORDER BY fetched_at ASC
+                     LIMIT $4 OFFSET $5"
+                )
+                .bind(from)
+                .bind(to)
+                .bind(step as f64)
+                .bind(limit)
+                .bind(offset)
+                .bind(norad_id)
+                .fetch_all(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query(
+                    "SELECT id, fetched_at, source_url, payload,
+                            norad_id, provider, latitude, longitude, altitude, velocity, visibility, position_at
+                     FROM iss_fetch_log
+                     WHERE norad_id = $5 AND fetched_at BETWEEN $1 AND $2
+                     ORDER BY fetched_at ASC
+                     LIMIT $3 OFFSET $4"
+                )
+                .bind(from)
+                .bind(to)
+                .bind(limit)
+                .bind(offset)
+                .bind(norad_id)
+                .fetch_all(&self.pool)
+                .await?
+            }
+        };
+
+        rows.iter()
+            .map(IssPosition::from_row)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))
+    }
+
+    /// Последние `limit` записей за период (по возрастанию времени): при ограничении
+    /// отбрасываются самые старые замеры, а не самые новые
+    pub async fn get_latest_in_range(
+        &self,
+        norad_id: i32,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
     ) -> Result<Vec<IssPosition>, ApiError> {
         let rows = sqlx::query(
-            "SELECT id, fetched_at, source_url, payload
+            "SELECT id, fetched_at, source_url, payload,
+                    norad_id, provider, latitude, longitude, altitude, velocity, visibility, position_at
              FROM iss_fetch_log
-             WHERE fetched_at BETWEEN $1 AND $2
-             ORDER BY fetched_at DESC"
+             WHERE norad_id = $4 AND fetched_at BETWEEN $1 AND $2
+             ORDER BY fetched_at DESC
+             LIMIT $3"
         )
         .bind(from)
         .bind(to)
+        .bind(limit)
+        .bind(norad_id)
         .fetch_all(&self.pool)
         .await?;
 
-        rows.iter()
+        let mut positions = rows
+            .iter()
             .map(IssPosition::from_row)
             .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+        positions.reverse();
+        Ok(positions)
+    }
+
+    /// Количество записей за период (с учётом прореживания)
+    pub async fn count_by_time_range(
+        &self,
+        norad_id: i32,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        step_sec: Option<i64>,
+    ) -> Result<i64, ApiError> {
+        let row = match step_sec {
+            Some(step) => {
+                sqlx::query(
+                    "SELECT COUNT(DISTINCT floor(extract(epoch FROM fetched_at) / $3)) as count
+                     FROM iss_fetch_log
+                     WHERE norad_id = $4 AND fetched_at BETWEEN $1 AND $2"
+                )
+                .bind(from)
+                .bind(to)
+                .bind(step as f64)
+                .bind(norad_id)
+                .fetch_one(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query(
+                    "SELECT COUNT(*) as count FROM iss_fetch_log
+                     WHERE norad_id = $3 AND fetched_at BETWEEN $1 AND $2"
+                )
+                .bind(from)
+                .bind(to)
+                .bind(norad_id)
+                .fetch_one(&self.pool)
+                .await?
+            }
+        };
+
+        Ok(row.get("count"))
+    }
+
+    /// Медианная высота по интервалам в `bucket_sec` секунд (только замеры с высотой);
+    /// время интервала - среднее время его замеров
+    pub async fn get_altitude_buckets(
+        &self,
+        norad_id: i32,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        bucket_sec: i64,
+    ) -> Result<Vec<AltitudeBucket>, ApiError> {
+        let rows = sqlx::query(
+            "SELECT floor(extract(epoch FROM fetched_at) / $4) AS bucket,
+                    AVG(extract(epoch FROM fetched_at))::DOUBLE PRECISION AS at_epoch,
+                    percentile_cont(0.5) WITHIN GROUP (ORDER BY altitude) AS altitude_km,
+                    COUNT(*) AS samples
+             FROM iss_fetch_log
+             WHERE norad_id = $1 AND fetched_at BETWEEN $2 AND $3 AND altitude IS NOT NULL
+             GROUP BY bucket
+             ORDER BY bucket"
+        )
+        .bind(norad_id)
+        .bind(from)
+        .bind(to)
+        .bind(bucket_sec as f64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let at_epoch: f64 = row.try_get("at_epoch")?;
+                Ok(AltitudeBucket {
+                    at: DateTime::from_timestamp(at_epoch as i64, 0).unwrap_or(from),
+                    altitude_km: row.try_get("altitude_km")?,
+                    samples: row.try_get("samples")?,
+                })
+            })
+            .collect::<Result<Vec<_>, sqlx::Error>>()
             .map_err(|e| ApiError::DatabaseError(e.to_string()))
     }
 
@@ -2,7 +2,13 @@
 pub mod iss_repo;
 pub mod osdr_repo;
 pub mod cache_repo;
+pub mod geofence_repo;
+pub mod tracked_object_repo;
+pub mod sync_run_repo;
 
 pub use iss_repo::IssRepo;
 pub use osdr_repo::OsdrRepo;
 pub use cache_repo::CacheRepo;
+pub use geofence_repo::GeofenceRepo;
+pub use tracked_object_repo::TrackedObjectRepo;
+pub use sync_run_repo::SyncRunRepo;
@@ -1,7 +1,26 @@
 use crate::domain::{osdr::*, ApiError};
 use chrono::{DateTime, Utc};
 use serde_json::Value;
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, QueryBuilder, Row};
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+
+/// Поисковый вектор по строке v пачки upsert: заголовок и dataset_id (A), описание (B), фасеты (C)
+const SEARCH_VECTOR_SQL: &str = "
+    setweight(to_tsvector('english', COALESCE(v.title, '')), 'A') ||
+    setweight(to_tsvector('simple', COALESCE(v.dataset_id, '')), 'A') ||
+    setweight(to_tsvector('english', COALESCE(v.description, '')), 'B') ||
+    setweight(to_tsvector('english', array_to_string(v.organisms || v.assay_types || v.factors, ' ')), 'C')";
+
+/// Условие поиска: $1 - запрос, $2..$4 - фильтры по фасетам
+const SEARCH_WHERE_SQL: &str = "search_vector @@ q
+    AND ($2::TEXT IS NULL OR $2 = ANY(organisms))
+    AND ($3::TEXT IS NULL OR $3 = ANY(assay_types))
+    AND ($4::TEXT IS NULL OR $4 = ANY(factors))";
+
+/// Строк манифеста в одном INSERT (7 параметров на строку)
+const FILES_INSERT_CHUNK: usize = 1000;
 
 /// Репозиторий для работы с данными OSDR
 pub struct OsdrRepo {
@@ -13,74 +32,759 @@ impl OsdrRepo {
         Self { pool }
     }
 
-    /// Upsert по бизнес-ключу (dataset_id)
+    /// Текущие content_hash датасетов (NULL - запись до появления хешей
+    /// или нормализованная прежней версией правил)
+    pub async fn content_hashes(&self, dataset_ids: &[String]) -> Result<HashMap<String, Option<String>>, ApiError> {
+        let rows = sqlx::query(
+            "SELECT dataset_id,
+                    CASE WHEN schema_version = $2 THEN content_hash END AS content_hash
+             FROM osdr_items
+             WHERE dataset_id = ANY($1)"
+        )
+        .bind(dataset_ids)
+        .bind(OSDR_SCHEMA_VERSION)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| Ok((row.try_get("dataset_id")?, row.try_get("content_hash")?)))
+            .collect::<Result<HashMap<_, _>, sqlx::Error>>()
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))
+    }
+
+    /// Upsert пачки по бизнес-ключу (dataset_id) одним многострочным INSERT в транзакции
     /// ОТЛИЧИЕ ОТ СЛЕПЫХ INSERT:
     /// - Избегаем дубликатов
     /// - Обновляем существующие записи при повторном получении
     /// - Используем уникальный индекс для эффективности
     /// - Атомарная операция (нет race conditions)
-    pub async fn upsert(
-        &self,
-        dataset_id: Option<String>,
-        title: Option<String>,
-        status: Option<String>,
-        updated_at: Option<DateTime<Utc>>,
-        raw: Value,
-    ) -> Result<i64, ApiError> {
-        let row = if let Some(ds_id) = dataset_id {
-            // Upsert с бизнес-ключом
-            sqlx::query(
-                "INSERT INTO osdr_items(dataset_id, title, status, updated_at, raw)
-                 VALUES($1,$2,$3,$4,$5)
-                 ON CONFLICT (dataset_id) DO UPDATE
-                 SET title=EXCLUDED.title, 
-                     status=EXCLUDED.status,
-                     updated_at=EXCLUDED.updated_at, 
-                     raw=EXCLUDED.raw
-                 RETURNING id"
-            )
-            .bind(ds_id)
-            .bind(title)
-            .bind(status)
-            .bind(updated_at)
-            .bind(raw)
-            .fetch_one(&self.pool)
-            .await?
-        } else {
-            // Без dataset_id - обычный insert
-            sqlx::query(
-                "INSERT INTO osdr_items(dataset_id, title, status, updated_at, raw)
-                 VALUES($1,$2,$3,$4,$5)
-                 RETURNING id"
-            )
-            .bind::<Option<String>>(None)
-            .bind(title)
-            .bind(status)
-            .bind(updated_at)
-            .bind(raw)
-            .fetch_one(&self.pool)
+    ///
+    /// Поисковый вектор пересчитывается при каждой записи.
+    /// Новые и изменившиеся документы записываются в osdr_item_versions в той же транзакции.
+    /// dataset_id в пачке должны быть уникальны.
+    pub async fn upsert_batch(&self, records: &[OsdrRecord]) -> Result<OsdrBatchResult, ApiError> {
+        let mut result = OsdrBatchResult::default();
+        if records.is_empty() {
+            return Ok(result);
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        // Прежние версии документов (строки блокируются до конца транзакции)
+        let dataset_ids: Vec<&str> = records.iter().map(|r| r.dataset_id.as_str()).collect();
+        let previous: HashMap<String, Value> =
+            sqlx::query("SELECT dataset_id, raw FROM osdr_items WHERE dataset_id = ANY($1) FOR UPDATE")
+                .bind(&dataset_ids)
+                .fetch_all(&mut *tx)
+                .await?
+                .iter()
+                .map(|row| (row.get("dataset_id"), row.get("raw")))
+                .collect();
+
+        let mut insert = QueryBuilder::<Postgres>::new(
+            "INSERT INTO osdr_items(dataset_id, title, status, updated_at, raw,
+                                    description, organisms, assay_types, factors, mission, content_hash,
+                                    search_vector, schema_version)
+             SELECT v.*, ",
+        );
+        insert
+            .push(SEARCH_VECTOR_SQL)
+            .push(", ")
+            .push_bind(OSDR_SCHEMA_VERSION)
+            .push(" FROM (");
+        insert.push_values(records, |mut row, r| {
+            row.push_bind(&r.dataset_id)
+                .push_bind(r.title.as_deref())
+                .push_bind(r.status.as_deref())
+                .push_bind(r.updated_at)
+                .push_bind(&r.raw)
+                .push_bind(r.metadata.description.as_deref())
+                .push_bind(&r.metadata.organisms)
+                .push_bind(&r.metadata.assay_types)
+                .push_bind(&r.metadata.factors)
+                .push_bind(r.metadata.mission.as_deref())
+                .push_bind(&r.content_hash);
+        });
+        // Условие совпадает с частичным индексом ux_osdr_dataset_id; строки с тем же хешем
+        // и той же версией нормализации не трогаем. Манифест файлов перезагружается только при
+        // смене содержимого: одна смена версии нормализации его не сбрасывает
+        insert.push(
+            ") AS v(dataset_id, title, status, updated_at, raw,
+                    description, organisms, assay_types, factors, mission, content_hash)
+             ON CONFLICT (dataset_id) WHERE dataset_id IS NOT NULL DO UPDATE
+             SET title=EXCLUDED.title,
+                 status=EXCLUDED.status,
+                 updated_at=EXCLUDED.updated_at,
+                 raw=EXCLUDED.raw,
+                 description=EXCLUDED.description,
+                 organisms=EXCLUDED.organisms,
+                 assay_types=EXCLUDED.assay_types,
+                 factors=EXCLUDED.factors,
+                 mission=EXCLUDED.mission,
+                 content_hash=EXCLUDED.content_hash,
+                 search_vector=EXCLUDED.search_vector,
+                 schema_version=EXCLUDED.schema_version,
+                 files_synced_at=CASE
+                     WHEN osdr_items.content_hash IS DISTINCT FROM EXCLUDED.content_hash THEN NULL
+                     ELSE osdr_items.files_synced_at
+                 END
+             WHERE osdr_items.content_hash IS DISTINCT FROM EXCLUDED.content_hash
+                OR osdr_items.schema_version IS DISTINCT FROM EXCLUDED.schema_version
+             RETURNING id, dataset_id",
+        );
+        let written: HashMap<String, i64> = insert
+            .build()
+            .fetch_all(&mut *tx)
             .await?
-        };
+            .iter()
+            .map(|row| (row.get("dataset_id"), row.get("id")))
+            .collect();
+
+        let mut versions = Vec::new();
+        for record in records {
+            let Some(&item_id) = written.get(&record.dataset_id) else {
+                result.unchanged += 1;
+                continue;
+            };
+            match previous.get(&record.dataset_id) {
+                None => {
+                    result.inserted += 1;
+                    versions.push((item_id, record, OsdrChangeKind::Created, Vec::new()));
+                }
+                Some(old) => {
+                    let diff = json_diff(old, &record.raw);
+                    if diff.is_empty() {
+                        // Запись без хеша: документ тот же, хеш заполнен
+                        result.unchanged += 1;
+                    } else {
+                        result.updated += 1;
+                        versions.push((item_id, record, OsdrChangeKind::Updated, diff));
+                    }
+                }
+            }
+        }
+
+        if !versions.is_empty() {
+            let diffs = versions
+                .iter()
+                .map(|(_, _, _, diff)| serde_json::to_value(diff))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| ApiError::InternalError(format!("Failed to serialize diff: {}", e)))?;
+
+            let mut history = QueryBuilder::<Postgres>::new(
+                "INSERT INTO osdr_item_versions(item_id, dataset_id, version, change, title, status, updated_at, raw, diff)
+                 SELECT v.item_id, v.dataset_id,
+                        COALESCE((SELECT MAX(version) FROM osdr_item_versions x WHERE x.item_id = v.item_id), 0) + 1,
+                        v.change, v.title, v.status, v.updated_at, v.raw, v.diff
+                 FROM (",
+            );
+            history.push_values(versions.iter().zip(diffs), |mut row, ((item_id, record, kind, _), diff)| {
+                row.push_bind(*item_id)
+                    .push_bind(&record.dataset_id)
+                    .push_bind(kind.as_str())
+                    .push_bind(record.title.as_deref())
+                    .push_bind(record.status.as_deref())
+                    .push_bind(record.updated_at)
+                    .push_bind(&record.raw)
+                    .push_bind(diff);
+            });
+            history.push(") AS v(item_id, dataset_id, change, title, status, updated_at, raw, diff)");
+            history.build().execute(&mut *tx).await?;
+        }
 
-        Ok(row.get("id"))
+        tx.commit().await?;
+        Ok(result)
     }
 
-    /// Получить список с пагинацией
-    pub async fn list(&self, limit: i64, offset: i64) -> Result<Vec<OsdrItem>, ApiError> {
-        let items = sqlx::query_as::<_, OsdrItem>(
-            "SELECT id, dataset_id, title, status, updated_at, inserted_at, raw
-             FROM osdr_items
-             ORDER BY inserted_at DESC
-             LIMIT $1 OFFSET $2"
+    /// Поместить отклонённые документы в карантин; повторно пришедший документ (тот же хеш)
+    /// обновляет last_seen_at и счётчик. content_hash в списке должны быть уникальны.
+    pub async fn quarantine(&self, rejected: &[OsdrRejected]) -> Result<(), ApiError> {
+        if rejected.is_empty() {
+            return Ok(());
+        }
+        let errors = rejected
+            .iter()
+            .map(|r| serde_json::to_value(&r.errors))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ApiError::InternalError(format!("Failed to serialize errors: {}", e)))?;
+
+        let mut insert = QueryBuilder::<Postgres>::new(
+            "INSERT INTO osdr_quarantine(dataset_id, content_hash, schema_version, errors, raw) ",
+        );
+        insert.push_values(rejected.iter().zip(errors), |mut row, (r, errors)| {
+            row.push_bind(r.dataset_id.as_deref())
+                .push_bind(&r.content_hash)
+                .push_bind(OSDR_SCHEMA_VERSION)
+                .push_bind(errors)
+                .push_bind(&r.raw);
+        });
+        insert.push(
+            " ON CONFLICT (content_hash) DO UPDATE
+             SET schema_version = EXCLUDED.schema_version,
+                 errors = EXCLUDED.errors,
+                 last_seen_at = NOW(),
+                 occurrences = osdr_quarantine.occurrences + 1",
+        );
+        insert.build().execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    /// Убрать из карантина датасеты, успешно нормализованные и сохранённые
+    pub async fn release_quarantined(&self, dataset_ids: &[&str]) -> Result<u64, ApiError> {
+        let result = sqlx::query("DELETE FROM osdr_quarantine WHERE dataset_id = ANY($1)")
+            .bind(dataset_ids)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Страница карантина от последних отклонённых
+    pub async fn quarantined(
+        &self,
+        dataset_id: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OsdrQuarantined>, ApiError> {
+        let rows = sqlx::query(
+            "SELECT id, dataset_id, schema_version, errors, raw, first_seen_at, last_seen_at, occurrences
+             FROM osdr_quarantine
+             WHERE ($1::TEXT IS NULL OR dataset_id = $1)
+             ORDER BY last_seen_at DESC, id DESC
+             LIMIT $2 OFFSET $3"
         )
+        .bind(dataset_id)
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pool)
         .await?;
 
+        rows.iter().map(quarantined_from_row).collect()
+    }
+
+    pub async fn count_quarantined(&self, dataset_id: Option<&str>) -> Result<i64, ApiError> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) as count FROM osdr_quarantine WHERE ($1::TEXT IS NULL OR dataset_id = $1)"
+        )
+        .bind(dataset_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get("count"))
+    }
+
+    /// Сохранённая позиция обхода каталога, начатого с `start_url`
+    pub async fn load_cursor(&self, start_url: &str) -> Result<Option<OsdrCursor>, ApiError> {
+        let row = sqlx::query("SELECT url, page FROM osdr_sync_cursor WHERE id = 1 AND start_url = $1")
+            .bind(start_url)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| OsdrCursor {
+            url: r.get("url"),
+            page: r.get::<i32, _>("page").max(1) as u32,
+        }))
+    }
+
+    /// Запомнить позицию обхода; None - каталог пройден, позиция сбрасывается
+    pub async fn save_cursor(&self, start_url: &str, cursor: Option<&OsdrCursor>) -> Result<(), ApiError> {
+        match cursor {
+            Some(cursor) => {
+                sqlx::query(
+                    "INSERT INTO osdr_sync_cursor(id, start_url, url, page, updated_at)
+                     VALUES (1, $1, $2, $3, NOW())
+                     ON CONFLICT (id) DO UPDATE
+                     SET start_url = EXCLUDED.start_url, url = EXCLUDED.url,
+                         page = EXCLUDED.page, updated_at = NOW()"
+                )
+                .bind(start_url)
+                .bind(&cursor.url)
+                .bind(cursor.page as i32)
+                .execute(&self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM osdr_sync_cursor WHERE id = 1")
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Датасеты OSDR без актуального манифеста файлов (новые и изменившиеся), свежие первыми
+    pub async fn datasets_pending_files(&self, limit: i64) -> Result<Vec<String>, ApiError> {
+        let rows = sqlx::query(
+            "SELECT dataset_id FROM osdr_items
+             WHERE files_synced_at IS NULL AND dataset_id LIKE 'OSD-%'
+             ORDER BY updated_at DESC NULLS LAST, id DESC
+             LIMIT $1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| row.get("dataset_id")).collect())
+    }
+
+    /// Сохранить манифест датасета и отметить время загрузки. Файлы сопоставляются по имени:
+    /// у оставшихся в манифесте id не меняется, исчезнувшие удаляются.
+    pub async fn replace_files(&self, dataset_id: &str, files: &[OsdrFileEntry]) -> Result<(), ApiError> {
+        let mut tx = self.pool.begin().await?;
+
+        let names: Vec<&str> = files.iter().map(|f| f.name.as_str()).collect();
+        sqlx::query("DELETE FROM osdr_files WHERE dataset_id = $1 AND NOT (name = ANY($2))")
+            .bind(dataset_id)
+            .bind(&names)
+            .execute(&mut *tx)
+            .await?;
+
+        for chunk in files.chunks(FILES_INSERT_CHUNK) {
+            let mut insert = QueryBuilder::<Postgres>::new(
+                "INSERT INTO osdr_files(dataset_id, name, size_bytes, category, subcategory, url, checksum) ",
+            );
+            insert.push_values(chunk, |mut row, f| {
+                row.push_bind(dataset_id)
+                    .push_bind(&f.name)
+                    .push_bind(f.size_bytes)
+                    .push_bind(f.category.as_deref())
+                    .push_bind(f.subcategory.as_deref())
+                    .push_bind(&f.url)
+                    .push_bind(f.checksum.as_deref());
+            });
+            insert.push(
+                " ON CONFLICT (dataset_id, name) DO UPDATE SET
+                    size_bytes = EXCLUDED.size_bytes,
+                    category = EXCLUDED.category,
+                    subcategory = EXCLUDED.subcategory,
+                    url = EXCLUDED.url,
+                    checksum = EXCLUDED.checksum,
+                    fetched_at = NOW()",
+            );
+            insert.build().execute(&mut *tx).await?;
+        }
+
+        sqlx::query("UPDATE osdr_items SET files_synced_at = NOW() WHERE dataset_id = $1")
+            .bind(dataset_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Время загрузки манифеста: None - датасета нет, Some(None) - манифест не загружался
+    pub async fn files_synced_at(&self, dataset_id: &str) -> Result<Option<Option<DateTime<Utc>>>, ApiError> {
+        let row = sqlx::query("SELECT files_synced_at FROM osdr_items WHERE dataset_id = $1")
+            .bind(dataset_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| r.get("files_synced_at")))
+    }
+
+    pub async fn files(&self, dataset_id: &str) -> Result<Vec<OsdrFile>, ApiError> {
+        let files = sqlx::query_as::<_, OsdrFile>(
+            "SELECT id, dataset_id, name, size_bytes, category, subcategory, url, checksum, fetched_at
+             FROM osdr_files
+             WHERE dataset_id = $1
+             ORDER BY category NULLS LAST, name, id"
+        )
+        .bind(dataset_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(files)
+    }
+
+    pub async fn file(&self, dataset_id: &str, file_id: i64) -> Result<Option<OsdrFile>, ApiError> {
+        let file = sqlx::query_as::<_, OsdrFile>(
+            "SELECT id, dataset_id, name, size_bytes, category, subcategory, url, checksum, fetched_at
+             FROM osdr_files
+             WHERE dataset_id = $1 AND id = $2"
+        )
+        .bind(dataset_id)
+        .bind(file_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(file)
+    }
+
+    /// Версии датасета от новых к старым
+    pub async fn versions(&self, item_id: i64) -> Result<Vec<OsdrVersion>, ApiError> {
+        let rows = sqlx::query(
+            "SELECT id, item_id, dataset_id, version, change, title, status, updated_at, diff, recorded_at
+             FROM osdr_item_versions
+             WHERE item_id = $1
+             ORDER BY version DESC"
+        )
+        .bind(item_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(version_from_row).collect()
+    }
+
+    /// Изменения после `since` от новых к старым; `change` - только созданные или только изменённые
+    pub async fn changes_since(
+        &self,
+        since: DateTime<Utc>,
+        change: Option<OsdrChangeKind>,
+        limit: i64,
+    ) -> Result<Vec<OsdrVersion>, ApiError> {
+        let rows = sqlx::query(
+            "SELECT id, item_id, dataset_id, version, change, title, status, updated_at, diff, recorded_at
+             FROM osdr_item_versions
+             WHERE recorded_at > $1 AND ($2::TEXT IS NULL OR change = $2)
+             ORDER BY recorded_at DESC, id DESC
+             LIMIT $3"
+        )
+        .bind(since)
+        .bind(change.map(|c| c.as_str()))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(version_from_row).collect()
+    }
+
+    /// Число изменений после `since`
+    pub async fn count_changes_since(
+        &self,
+        since: DateTime<Utc>,
+        change: Option<OsdrChangeKind>,
+    ) -> Result<i64, ApiError> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) as count FROM osdr_item_versions
+             WHERE recorded_at > $1 AND ($2::TEXT IS NULL OR change = $2)"
+        )
+        .bind(since)
+        .bind(change.map(|c| c.as_str()))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get("count"))
+    }
+
+    /// Получить страницу списка с фильтром и сортировкой
+    pub async fn list(
+        &self,
+        filter: &OsdrFilter,
+        sort: OsdrSort,
+        order: SortOrder,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OsdrItem>, ApiError> {
+        // Колонка и направление - из enum, id - для стабильного порядка при равных значениях
+        let sql = format!(
+            "SELECT id, dataset_id, title, status, updated_at, inserted_at, raw
+             FROM osdr_items
+             WHERE ($1::TEXT IS NULL OR LOWER(status) = LOWER($1))
+               AND ($2::TIMESTAMPTZ IS NULL OR updated_at >= $2)
+             ORDER BY {column} {order} NULLS LAST, id {order}
+             LIMIT $3 OFFSET $4",
+            column = sort.column(),
+            order = order.sql(),
+        );
+
+        let items = sqlx::query_as::<_, OsdrItem>(&sql)
+            .bind(filter.status.as_deref())
+            .bind(filter.updated_since)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
         Ok(items)
     }
 
+    /// Количество записей под фильтром
+    pub async fn count_filtered(&self, filter: &OsdrFilter) -> Result<i64, ApiError> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) as count FROM osdr_items
+             WHERE ($1::TEXT IS NULL OR LOWER(status) = LOWER($1))
+               AND ($2::TIMESTAMPTZ IS NULL OR updated_at >= $2)"
+        )
+        .bind(filter.status.as_deref())
+        .bind(filter.updated_since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get("count"))
+    }
+
+    /// Полнотекстовый поиск: страница результатов по убыванию релевантности
+    pub async fn search(
+        &self,
+        query: &str,
+        facets: &OsdrFacetFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OsdrSearchHit>, ApiError> {
+        let sql = format!(
+            "SELECT id, dataset_id, title, status, updated_at, organisms, assay_types, factors,
+                    ts_rank_cd(search_vector, q) AS rank,
+                    ts_headline('english', COALESCE(title, ''), q, 'HighlightAll=true, StartSel=<mark>, StopSel=</mark>') AS title_highlight,
+                    CASE WHEN description IS NOT NULL THEN
+                        ts_headline('english', description, q,
+                            'StartSel=<mark>, StopSel=</mark>, MaxWords=35, MinWords=15, MaxFragments=2')
+                    END AS snippet
+             FROM osdr_items, websearch_to_tsquery('english', $1) q
+             WHERE {}
+             ORDER BY rank DESC, id DESC
+             LIMIT $5 OFFSET $6",
+            SEARCH_WHERE_SQL
+        );
+
+        let rows = sqlx::query(&sql)
+            .bind(query)
+            .bind(facets.organism.as_deref())
+            .bind(facets.assay_type.as_deref())
+            .bind(facets.factor.as_deref())
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(OsdrSearchHit {
+                    id: row.try_get("id")?,
+                    dataset_id: row.try_get("dataset_id")?,
+                    title: row.try_get("title")?,
+                    status: row.try_get("status")?,
+                    updated_at: row.try_get("updated_at")?,
+                    rank: row.try_get("rank")?,
+                    title_highlight: row.try_get("title_highlight")?,
+                    snippet: row.try_get("snippet")?,
+                    organisms: row.try_get::<Option<Vec<String>>, _>("organisms")?.unwrap_or_default(),
+                    assay_types: row.try_get::<Option<Vec<String>>, _>("assay_types")?.unwrap_or_default(),
+                    factors: row.try_get::<Option<Vec<String>>, _>("factors")?.unwrap_or_default(),
+                })
+            })
+            .collect::<Result<Vec<_>, sqlx::Error>>()
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))
+    }
+
+    /// Число результатов поиска
+    pub async fn search_count(&self, query: &str, facets: &OsdrFacetFilter) -> Result<i64, ApiError> {
+        let sql = format!(
+            "SELECT COUNT(*) as count
+             FROM osdr_items, websearch_to_tsquery('english', $1) q
+             WHERE {}",
+            SEARCH_WHERE_SQL
+        );
+
+        let row = sqlx::query(&sql)
+            .bind(query)
+            .bind(facets.organism.as_deref())
+            .bind(facets.assay_type.as_deref())
+            .bind(facets.factor.as_deref())
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(row.get("count"))
+    }
+
+    /// Фасеты по всем результатам поиска: до `per_facet` самых частых значений
+    pub async fn search_facets(
+        &self,
+        query: &str,
+        facets: &OsdrFacetFilter,
+        per_facet: i64,
+    ) -> Result<OsdrFacets, ApiError> {
+        let sql = format!(
+            "WITH matched AS (
+                 SELECT organisms, assay_types, factors
+                 FROM osdr_items, websearch_to_tsquery('english', $1) q
+                 WHERE {}
+             ),
+             counted AS (
+                 SELECT 'organism' AS facet, v AS value, COUNT(*) AS count
+                 FROM matched, unnest(organisms) v GROUP BY v
+                 UNION ALL
+                 SELECT 'assay_type', v, COUNT(*) FROM matched, unnest(assay_types) v GROUP BY v
+                 UNION ALL
+                 SELECT 'factor', v, COUNT(*) FROM matched, unnest(factors) v GROUP BY v
+             )
+             SELECT facet, value, count FROM (
+                 SELECT facet, value, count,
+                        ROW_NUMBER() OVER (PARTITION BY facet ORDER BY count DESC, value) AS n
+                 FROM counted
+             ) ranked
+             WHERE n <= $5
+             ORDER BY facet, count DESC, value",
+            SEARCH_WHERE_SQL
+        );
+
+        let rows = sqlx::query(&sql)
+            .bind(query)
+            .bind(facets.organism.as_deref())
+            .bind(facets.assay_type.as_deref())
+            .bind(facets.factor.as_deref())
+            .bind(per_facet)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut result = OsdrFacets::default();
+        for row in &rows {
+            let facet: String = row.try_get("facet")?;
+            let value = FacetValue {
+                value: row.try_get("value")?,
+                count: row.try_get("count")?,
+            };
+            match facet.as_str() {
+                "organism" => result.organism.push(value),
+                "assay_type" => result.assay_type.push(value),
+                _ => result.factor.push(value),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Статистика каталога: распределения по статусу, организму, типу assay и миссии
+    /// (до `per_group` значений), месяцы обновления (последние `months`), самый новый и старый датасет
+    pub async fn stats(&self, per_group: i64, months: i64) -> Result<OsdrStats, ApiError> {
+        let mut stats = OsdrStats::default();
+
+        let rows = sqlx::query(
+            "WITH counted AS (
+                 SELECT 'status' AS grp, COALESCE(NULLIF(status, ''), 'unknown') AS value, COUNT(*) AS count
+                 FROM osdr_items GROUP BY 2
+                 UNION ALL
+                 SELECT 'organism', v, COUNT(*) FROM osdr_items, unnest(organisms) v GROUP BY v
+                 UNION ALL
+                 SELECT 'assay_type', v, COUNT(*) FROM osdr_items, unnest(assay_types) v GROUP BY v
+                 UNION ALL
+                 SELECT 'mission', mission, COUNT(*) FROM osdr_items WHERE mission IS NOT NULL GROUP BY mission
+             )
+             SELECT grp, value, count FROM (
+                 SELECT grp, value, count,
+                        ROW_NUMBER() OVER (PARTITION BY grp ORDER BY count DESC, value) AS n
+                 FROM counted
+             ) ranked
+             WHERE n <= $1
+             ORDER BY grp, count DESC, value"
+        )
+        .bind(per_group)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for row in &rows {
+            let group: String = row.try_get("grp")?;
+            let value = FacetValue {
+                value: row.try_get("value")?,
+                count: row.try_get("count")?,
+            };
+            match group.as_str() {
+                "status" => stats.by_status.push(value),
+                "organism" => stats.by_organism.push(value),
+                "assay_type" => stats.by_assay_type.push(value),
+                _ => stats.by_mission.push(value),
+            }
+        }
+
+        let rows = sqlx::query(
+            "SELECT month, count FROM (
+                 SELECT to_char(date_trunc('month', updated_at AT TIME ZONE 'UTC'), 'YYYY-MM') AS month,
+                        COUNT(*) AS count
+                 FROM osdr_items
+                 WHERE updated_at IS NOT NULL
+                 GROUP BY 1
+                 ORDER BY 1 DESC
+                 LIMIT $1
+             ) recent
+             ORDER BY month"
+        )
+        .bind(months)
+        .fetch_all(&self.pool)
+        .await?;
+
+        stats.by_month = rows
+            .iter()
+            .map(|row| {
+                Ok(OsdrMonthCount {
+                    month: row.try_get("month")?,
+                    count: row.try_get("count")?,
+                })
+            })
+            .collect::<Result<_, sqlx::Error>>()?;
+
+        // Итог и границы периода; датасеты без updated_at в границы не попадают
+        let rows = sqlx::query(
+            "SELECT 'total' AS edge, NULL AS dataset_id, NULL AS title, NULL::TIMESTAMPTZ AS updated_at,
+                    COUNT(*) AS count
+             FROM osdr_items
+             UNION ALL
+             (SELECT 'newest', dataset_id, title, updated_at, NULL FROM osdr_items
+              WHERE updated_at IS NOT NULL ORDER BY updated_at DESC, id DESC LIMIT 1)
+             UNION ALL
+             (SELECT 'oldest', dataset_id, title, updated_at, NULL FROM osdr_items
+              WHERE updated_at IS NOT NULL ORDER BY updated_at, id LIMIT 1)"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for row in &rows {
+            let edge: String = row.try_get("edge")?;
+            if edge == "total" {
+                stats.total = row.try_get("count")?;
+                continue;
+            }
+            let dataset = OsdrStatsDataset {
+                dataset_id: row.try_get("dataset_id")?,
+                title: row.try_get("title")?,
+                updated_at: row.try_get("updated_at")?,
+            };
+            if edge == "newest" {
+                stats.newest = Some(dataset);
+            } else {
+                stats.oldest = Some(dataset);
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Все записи под фильтром списка в порядке сортировки - построчно в `rows`, без загрузки
+    /// результата в память. Ошибка запроса передаётся последним элементом; закрытый получатель
+    /// останавливает чтение.
+    pub async fn export(
+        &self,
+        filter: &OsdrFilter,
+        sort: OsdrSort,
+        order: SortOrder,
+        rows: mpsc::Sender<Result<OsdrExportRow, ApiError>>,
+    ) {
+        let sql = format!(
+            "SELECT id, dataset_id, title, status, mission, organisms, assay_types, factors,
+                    updated_at, inserted_at, description
+             FROM osdr_items
+             WHERE ($1::TEXT IS NULL OR LOWER(status) = LOWER($1))
+               AND ($2::TIMESTAMPTZ IS NULL OR updated_at >= $2)
+             ORDER BY {column} {order} NULLS LAST, id {order}",
+            column = sort.column(),
+            order = order.sql(),
+        );
+
+        let mut stream = sqlx::query_as::<_, OsdrExportRow>(&sql)
+            .bind(filter.status.as_deref())
+            .bind(filter.updated_since)
+            .fetch(&self.pool);
+
+        while let Some(row) = stream.next().await {
+            let row = row.map_err(ApiError::from);
+            let failed = row.is_err();
+            if rows.send(row).await.is_err() || failed {
+                break;
+            }
+        }
+    }
+
     /// Подсчёт общего количества
     pub async fn count(&self) -> Result<i64, ApiError> {
         let row = sqlx::query("SELECT COUNT(*) as count FROM osdr_items")
@@ -104,3 +808,39 @@ impl OsdrRepo {
         Ok(item)
     }
 }
+
+fn version_from_row(row: &sqlx::postgres::PgRow) -> Result<OsdrVersion, ApiError> {
+    let change: String = row.try_get("change")?;
+    let diff: Value = row.try_get("diff")?;
+
+    Ok(OsdrVersion {
+        id: row.try_get("id")?,
+        item_id: row.try_get("item_id")?,
+        dataset_id: row.try_get("dataset_id")?,
+        version: row.try_get("version")?,
+        change: OsdrChangeKind::parse(&change)
+            .ok_or_else(|| ApiError::DatabaseError(format!("Unknown change kind '{}'", change)))?,
+        title: row.try_get("title")?,
+        status: row.try_get("status")?,
+        updated_at: row.try_get("updated_at")?,
+        diff: serde_json::from_value(diff)
+            .map_err(|e| ApiError::DatabaseError(format!("Invalid diff: {}", e)))?,
+        recorded_at: row.try_get("recorded_at")?,
+    })
+}
+
+fn quarantined_from_row(row: &sqlx::postgres::PgRow) -> Result<OsdrQuarantined, ApiError> {
+    let errors: Value = row.try_get("errors")?;
+
+    Ok(OsdrQuarantined {
+        id: row.try_get("id")?,
+        dataset_id: row.try_get("dataset_id")?,
+        schema_version: row.try_get("schema_version")?,
+        errors: serde_json::from_value(errors)
+            .map_err(|e| ApiError::DatabaseError(format!("Invalid quarantine errors: {}", e)))?,
+        raw: row.try_get("raw")?,
+        first_seen_at: row.try_get("first_seen_at")?,
+        last_seen_at: row.try_get("last_seen_at")?,
+        occurrences: row.try_get("occurrences")?,
+    })
+}
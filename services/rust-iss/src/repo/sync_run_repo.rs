@@ -0,0 +1,142 @@
+use crate::domain::{sync_run::*, ApiError};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+
+/// Условие фильтра журнала: $1 - задача, $2 - источник запуска, $3 - итог, $4 - начало не раньше
+const FILTER_SQL: &str = "($1::TEXT IS NULL OR job = $1)
+    AND ($2::TEXT IS NULL OR trigger = $2)
+    AND ($3::TEXT IS NULL OR outcome = $3)
+    AND ($4::TIMESTAMPTZ IS NULL OR started_at >= $4)";
+
+/// Репозиторий журнала запусков задач
+pub struct SyncRunRepo {
+    pool: PgPool,
+}
+
+impl SyncRunRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Начало запуска (outcome = running)
+    pub async fn start(&self, job: &str, trigger: RunTrigger) -> Result<i64, ApiError> {
+        let row = sqlx::query(
+            "INSERT INTO sync_runs (job, trigger, outcome)
+             VALUES ($1, $2, 'running')
+             RETURNING id"
+        )
+        .bind(job)
+        .bind(trigger.as_str())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get("id"))
+    }
+
+    /// Итог запуска; длительность считается от started_at
+    pub async fn finish(
+        &self,
+        id: i64,
+        outcome: RunOutcome,
+        summary: Option<&RunSummary>,
+        error: Option<(&str, &str)>,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            "UPDATE sync_runs
+             SET finished_at = NOW(),
+                 duration_ms = (EXTRACT(EPOCH FROM NOW() - started_at) * 1000)::BIGINT,
+                 outcome = $2,
+                 items = $3,
+                 failed_items = $4,
+                 error_code = $5,
+                 error_message = $6,
+                 details = $7
+             WHERE id = $1"
+        )
+        .bind(id)
+        .bind(outcome.as_str())
+        .bind(summary.map(|s| s.items))
+        .bind(summary.map(|s| s.failed))
+        .bind(error.map(|(code, _)| code))
+        .bind(error.map(|(_, message)| message))
+        .bind(summary.and_then(|s| s.details.as_ref()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Страница журнала от новых запусков к старым
+    pub async fn list(&self, filter: &SyncRunFilter, limit: i64, offset: i64) -> Result<Vec<SyncRun>, ApiError> {
+        let sql = format!(
+            "SELECT id, job, trigger, started_at, finished_at, duration_ms, outcome,
+                    items, failed_items, error_code, error_message, details
+             FROM sync_runs
+             WHERE {}
+             ORDER BY started_at DESC, id DESC
+             LIMIT $5 OFFSET $6",
+            FILTER_SQL
+        );
+
+        let rows = sqlx::query(&sql)
+            .bind(filter.job.as_deref())
+            .bind(filter.trigger.map(|t| t.as_str()))
+            .bind(filter.outcome.map(|o| o.as_str()))
+            .bind(filter.since)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(run_from_row).collect()
+    }
+
+    pub async fn count(&self, filter: &SyncRunFilter) -> Result<i64, ApiError> {
+        let sql = format!("SELECT COUNT(*) as count FROM sync_runs WHERE {}", FILTER_SQL);
+
+        let row = sqlx::query(&sql)
+            .bind(filter.job.as_deref())
+            .bind(filter.trigger.map(|t| t.as_str()))
+            .bind(filter.outcome.map(|o| o.as_str()))
+            .bind(filter.since)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(row.get("count"))
+    }
+
+    /// Удалить завершённые запуски, начатые раньше `before`
+    pub async fn purge(&self, before: DateTime<Utc>) -> Result<u64, ApiError> {
+        let result = sqlx::query(
+            "DELETE FROM sync_runs
+             WHERE started_at < $1 AND outcome <> 'running'"
+        )
+        .bind(before)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+fn run_from_row(row: &sqlx::postgres::PgRow) -> Result<SyncRun, ApiError> {
+    let trigger: String = row.try_get("trigger")?;
+    let outcome: String = row.try_get("outcome")?;
+
+    Ok(SyncRun {
+        id: row.try_get("id")?,
+        job: row.try_get("job")?,
+        trigger: RunTrigger::parse(&trigger)
+            .ok_or_else(|| ApiError::DatabaseError(format!("Unknown run trigger '{}'", trigger)))?,
+        started_at: row.try_get("started_at")?,
+        finished_at: row.try_get("finished_at")?,
+        duration_ms: row.try_get("duration_ms")?,
+        outcome: RunOutcome::parse(&outcome)
+            .ok_or_else(|| ApiError::DatabaseError(format!("Unknown run outcome '{}'", outcome)))?,
+        items: row.try_get("items")?,
+        failed_items: row.try_get("failed_items")?,
+        error_code: row.try_get("error_code")?,
+        error_message: row.try_get("error_message")?,
+        details: row.try_get("details")?,
+    })
+}
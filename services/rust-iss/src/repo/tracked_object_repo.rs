@@ -0,0 +1,77 @@
+use crate::domain::{satellite::*, ApiError};
+use sqlx::PgPool;
+
+/// Репозиторий реестра отслеживаемых объектов
+pub struct TrackedObjectRepo {
+    pool: PgPool,
+}
+
+impl TrackedObjectRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Все объекты; `enabled_only` - только те, что опрашивает планировщик
+    pub async fn list(&self, enabled_only: bool) -> Result<Vec<TrackedObject>, ApiError> {
+        let rows = sqlx::query(
+            "SELECT norad_id, name, poll_every_seconds, enabled, created_at
+             FROM tracked_objects
+             WHERE enabled OR NOT $1
+             ORDER BY norad_id"
+        )
+        .bind(enabled_only)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(TrackedObject::from_row)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))
+    }
+
+    pub async fn get(&self, norad_id: i32) -> Result<Option<TrackedObject>, ApiError> {
+        let row_opt = sqlx::query(
+            "SELECT norad_id, name, poll_every_seconds, enabled, created_at
+             FROM tracked_objects WHERE norad_id = $1"
+        )
+        .bind(norad_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row_opt {
+            Some(row) => Ok(Some(TrackedObject::from_row(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Добавить объект или обновить существующий
+    pub async fn upsert(&self, input: &TrackedObjectInput) -> Result<TrackedObject, ApiError> {
+        let row = sqlx::query(
+            "INSERT INTO tracked_objects (norad_id, name, poll_every_seconds, enabled)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (norad_id) DO UPDATE SET
+                name = EXCLUDED.name,
+                poll_every_seconds = EXCLUDED.poll_every_seconds,
+                enabled = EXCLUDED.enabled
+             RETURNING norad_id, name, poll_every_seconds, enabled, created_at"
+        )
+        .bind(input.norad_id)
+        .bind(&input.name)
+        .bind(input.poll_every_seconds)
+        .bind(input.enabled)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(TrackedObject::from_row(&row)?)
+    }
+
+    /// Удалить объект из реестра (история в iss_fetch_log сохраняется)
+    pub async fn delete(&self, norad_id: i32) -> Result<bool, ApiError> {
+        let result = sqlx::query("DELETE FROM tracked_objects WHERE norad_id = $1")
+            .bind(norad_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -33,11 +33,59 @@ pub fn create_router(state: AppState) -> Router {
         .route("/last", get(iss_handlers::get_last))
         .route("/fetch", get(iss_handlers::trigger_fetch))
         .route("/iss/trend", get(iss_handlers::get_trend))
+        .route("/iss/range", get(iss_handlers::get_range))
+        .route("/iss/history", get(iss_handlers::get_history))
+        .route("/iss/predict", get(iss_handlers::predict))
+        .route("/iss/track", get(iss_handlers::get_track))
+        .route("/iss/track.geojson", get(iss_handlers::export_track_geojson))
+        .route("/iss/track.kml", get(iss_handlers::export_track_kml))
+        .route("/iss/passes", get(iss_handlers::get_passes))
+        .route("/iss/altitude/analysis", get(iss_handlers::altitude_analysis))
+        .route("/iss/illumination", get(iss_handlers::get_illumination))
+        .route("/solar/terminator", get(solar_handlers::get_terminator))
+        .route("/iss/events", get(geofence_handlers::get_events))
+        .route("/geofences", get(geofence_handlers::list_geofences).post(geofence_handlers::create_geofence))
+        .route(
+            "/geofences/:id",
+            get(geofence_handlers::get_geofence)
+                .put(geofence_handlers::update_geofence)
+                .delete(geofence_handlers::delete_geofence),
+        )
+        .route("/iss/stream", get(stream_handlers::iss_sse))
+        .route("/sat", get(satellite_handlers::list_objects).post(satellite_handlers::upsert_object))
+        .route(
+            "/sat/:norad",
+            get(satellite_handlers::get_object).delete(satellite_handlers::delete_object),
+        )
+        .route("/sat/:norad/last", get(satellite_handlers::get_last))
+        .route("/sat/:norad/history", get(satellite_handlers::get_history))
+        .route("/sat/:norad/fetch", get(satellite_handlers::trigger_fetch))
+        .route("/sat/:norad/trend", get(satellite_handlers::get_trend))
+        .route("/sat/:norad/range", get(satellite_handlers::get_range))
+        .route("/sat/:norad/predict", get(satellite_handlers::predict))
+        .route("/sat/:norad/track", get(satellite_handlers::get_track))
+        .route("/sat/:norad/track.geojson", get(satellite_handlers::export_track_geojson))
+        .route("/sat/:norad/track.kml", get(satellite_handlers::export_track_kml))
+        .route("/sat/:norad/passes", get(satellite_handlers::get_passes))
+        .route("/sat/:norad/altitude/analysis", get(satellite_handlers::altitude_analysis))
+        .route("/sat/:norad/illumination", get(satellite_handlers::get_illumination))
+        .route("/sat/:norad/stream", get(stream_handlers::sat_sse))
+        .route("/ws/iss", get(stream_handlers::iss_ws))
         .route("/osdr/sync", get(osdr_handlers::sync_osdr))
         .route("/osdr/list", get(osdr_handlers::list_osdr))
+        .route("/osdr/search", get(osdr_handlers::search_osdr))
+        .route("/osdr/changes", get(osdr_handlers::get_changes))
+        .route("/osdr/stats", get(osdr_handlers::get_stats))
+        .route("/osdr/export", get(osdr_handlers::export_osdr))
+        .route("/osdr/quarantine", get(osdr_handlers::list_quarantine))
+        .route("/osdr/:dataset_id", get(osdr_handlers::get_dataset))
+        .route("/osdr/:dataset_id/history", get(osdr_handlers::get_history))
+        .route("/osdr/:dataset_id/files", get(osdr_handlers::list_files))
+        .route("/osdr/:dataset_id/files/:file_id", get(osdr_handlers::download_file))
         .route("/space/:src/latest", get(space_handlers::get_latest))
         .route("/space/refresh", get(space_handlers::refresh_sources))
         .route("/space/summary", get(space_handlers::get_summary))
+        .route("/admin/jobs/runs", get(admin_handlers::list_runs))
         .layer(governor_layer)
         
         .with_state(state)
@@ -0,0 +1,76 @@
+use crate::{
+    domain::{
+        iss::altitude::{self, AltitudeAnalysis},
+        ApiError,
+    },
+    repo::IssRepo,
+};
+use chrono::{Duration, Utc};
+
+/// Максимальное окно анализа высоты (в днях)
+pub const ALTITUDE_MAX_DAYS: i64 = 365;
+/// Максимальный интервал агрегации (в часах)
+pub const ALTITUDE_MAX_BUCKET_HOURS: i64 = 168;
+/// Допустимый порог скачка высоты для манёвра (в метрах)
+pub const REBOOST_THRESHOLD_RANGE_M: (f64, f64) = (50.0, 10000.0);
+
+/// Анализ истории высот из iss_fetch_log: снижение орбиты и манёвры подъёма
+pub struct AltitudeService {
+    repo: IssRepo,
+}
+
+impl AltitudeService {
+    pub fn new(repo: IssRepo) -> Self {
+        Self { repo }
+    }
+
+    /// Анализ за последние `days` суток; высота усредняется медианой по `bucket_hours`,
+    /// чтобы убрать колебания вдоль витка (эллипсоид и эксцентриситет)
+    pub async fn analyze(
+        &self,
+        norad_id: i32,
+        days: i64,
+        bucket_hours: i64,
+        reboost_threshold_m: f64,
+    ) -> Result<AltitudeAnalysis, ApiError> {
+        if !(1..=ALTITUDE_MAX_DAYS).contains(&days) {
+            return Err(ApiError::ValidationError(format!(
+                "'days' must be between 1 and {}", ALTITUDE_MAX_DAYS
+            )));
+        }
+        if !(1..=ALTITUDE_MAX_BUCKET_HOURS).contains(&bucket_hours) {
+            return Err(ApiError::ValidationError(format!(
+                "'bucket_hours' must be between 1 and {}", ALTITUDE_MAX_BUCKET_HOURS
+            )));
+        }
+        let (min_threshold, max_threshold) = REBOOST_THRESHOLD_RANGE_M;
+        if !(min_threshold..=max_threshold).contains(&reboost_threshold_m) {
+            return Err(ApiError::ValidationError(format!(
+                "'threshold_m' must be between {} and {}", min_threshold, max_threshold
+            )));
+        }
+
+        let to = Utc::now();
+        let from = to - Duration::days(days);
+        let buckets = self
+            .repo
+            .get_altitude_buckets(norad_id, from, to, bucket_hours * 3600)
+            .await?;
+
+        let (segments, reboosts) = altitude::detect(&buckets, reboost_threshold_m / 1000.0);
+
+        Ok(AltitudeAnalysis {
+            norad_id,
+            from,
+            to,
+            bucket_hours,
+            reboost_threshold_m,
+            samples: buckets.iter().map(|b| b.samples).sum(),
+            decay_rate_m_per_day: altitude::weighted_decay_rate(&segments),
+            current_altitude_km: buckets.last().map(|b| b.altitude_km),
+            segments,
+            reboosts,
+            buckets,
+        })
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{
+    domain::{
+        geofence::*,
+        iss::IssPosition,
+        ApiError,
+    },
+    repo::GeofenceRepo,
+};
+use chrono::{DateTime, Duration, Utc};
+
+/// Максимальная длина имени геозоны
+pub const GEOFENCE_NAME_MAX_LEN: usize = 200;
+/// Максимальное число вершин полигона
+pub const GEOFENCE_MAX_POINTS: usize = 1000;
+/// Максимальный размер выборки /iss/events
+pub const EVENTS_MAX_LIMIT: i64 = 1000;
+/// Период /iss/events по умолчанию (в днях)
+pub const EVENTS_DEFAULT_DAYS: i64 = 7;
+
+/// Сервис геозон: CRUD и фиксация входа/выхода МКС
+pub struct GeofenceService {
+    repo: GeofenceRepo,
+}
+
+impl GeofenceService {
+    pub fn new(repo: GeofenceRepo) -> Self {
+        Self { repo }
+    }
+
+    fn validate(input: &GeofenceInput) -> Result<(), ApiError> {
+        let name = input.name.trim();
+        if name.is_empty() || name.chars().count() > GEOFENCE_NAME_MAX_LEN {
+            return Err(ApiError::ValidationError(format!(
+                "'name' must be 1..{} characters", GEOFENCE_NAME_MAX_LEN
+            )));
+        }
+        if let GeofenceShape::Polygon { coordinates } = &input.shape {
+            if coordinates.len() > GEOFENCE_MAX_POINTS {
+                return Err(ApiError::ValidationError(format!(
+                    "polygon must have at most {} points", GEOFENCE_MAX_POINTS
+                )));
+            }
+        }
+        input.shape.validate().map_err(ApiError::ValidationError)
+    }
+
+    pub async fn list(&self) -> Result<Vec<Geofence>, ApiError> {
+        self.repo.list().await
+    }
+
+    pub async fn get(&self, id: i64) -> Result<Geofence, ApiError> {
+        self.repo.get(id).await?
+            .ok_or_else(|| ApiError::NotFound(format!("geofence {} not found", id)))
+    }
+
+    pub async fn create(&self, mut input: GeofenceInput) -> Result<Geofence, ApiError> {
+        Self::validate(&input)?;
+        input.name = input.name.trim().to_string();
+        self.repo.insert(&input).await
+    }
+
+    pub async fn update(&self, id: i64, mut input: GeofenceInput) -> Result<Geofence, ApiError> {
+        Self::validate(&input)?;
+        input.name = input.name.trim().to_string();
+        self.repo.update(id, &input).await?
+            .ok_or_else(|| ApiError::NotFound(format!("geofence {} not found", id)))
+    }
+
+    pub async fn delete(&self, id: i64) -> Result<(), ApiError> {
+        if !self.repo.delete(id).await? {
+            return Err(ApiError::NotFound(format!("geofence {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// События входа/выхода за период (по умолчанию - последние 7 дней)
+    pub async fn events(
+        &self,
+        geofence_id: Option<i64>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> Result<Vec<GeofenceEvent>, ApiError> {
+        if !(1..=EVENTS_MAX_LIMIT).contains(&limit) {
+            return Err(ApiError::ValidationError(format!(
+                "'limit' must be between 1 and {}", EVENTS_MAX_LIMIT
+            )));
+        }
+        let to = to.unwrap_or_else(Utc::now);
+        let from = from.unwrap_or(to - Duration::days(EVENTS_DEFAULT_DAYS));
+        if from > to {
+            return Err(ApiError::ValidationError("'from' must not be after 'to'".to_string()));
+        }
+
+        self.repo.list_events(geofence_id, from, to, limit).await
+    }
+
+    /// Сравнить два последовательных замера и записать пересечения границ геозон.
+    /// Без предыдущего замера (или без координат) пересечение не определяется.
+    pub async fn evaluate(
+        &self,
+        previous: Option<&IssPosition>,
+        current: &IssPosition,
+    ) -> Result<Vec<GeofenceTransition>, ApiError> {
+        let (Some(prev_lat), Some(prev_lon)) = (
+            previous.and_then(|p| p.latitude),
+            previous.and_then(|p| p.longitude),
+        ) else {
+            return Ok(Vec::new());
+        };
+        let (Some(lat), Some(lon)) = (current.latitude, current.longitude) else {
+            return Ok(Vec::new());
+        };
+        let occurred_at = current.position_at.unwrap_or(current.fetched_at);
+
+        let mut transitions = Vec::new();
+        for geofence in self.repo.list().await? {
+            let was_inside = geofence.shape.contains(prev_lat, prev_lon);
+            let is_inside = geofence.shape.contains(lat, lon);
+            if let Some(transition) = GeofenceTransition::between(was_inside, is_inside) {
+                self.repo
+                    .insert_event(&geofence, transition, occurred_at, current.id, lat, lon)
+                    .await?;
+                transitions.push(transition);
+            }
+        }
+
+        Ok(transitions)
+    }
+}
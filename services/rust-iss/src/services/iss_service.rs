@@ -1,99 +1,700 @@
 use crate::{
-    clients::IssClient,
-    domain::{iss::*, ApiError},
-    repo::IssRepo,
+    clients::{IssClient, IssPositionProvider},
+    domain::{
+        iss::{
+            altitude::linear_slope,
+            ground_track::{split_antimeridian, TrackSample, TrackSource},
+            orbit::{subpoint, Geodetic, Sgp4, Tle},
+            passes::find_passes,
+            *,
+        },
+        solar::{is_sunlit, is_sunlit_geodetic, solar_elevation, subsolar_point, visibility_label},
+        satellite::{tle_cache_source, TrackedObject, TrackedObjectInput, ISS_NORAD_ID},
+        ApiError,
+    },
+    repo::{CacheRepo, IssRepo, TrackedObjectRepo},
+    services::GeofenceService,
 };
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::warn;
 
-/// Сервис для работы с данными МКС
+/// Максимальный размер страницы для /iss/range
+pub const RANGE_MAX_LIMIT: i64 = 1000;
+/// Максимальная длина периода для /iss/range (в днях)
+pub const RANGE_MAX_DAYS: i64 = 31;
+/// Допустимое удаление момента прогноза от эпохи TLE (в днях)
+pub const PREDICT_MAX_DAYS: i64 = 14;
+/// Максимальное число точек в /iss/track
+pub const TRACK_MAX_POINTS: i64 = 5000;
+/// Максимальное окно поиска пролётов (в днях)
+pub const PASSES_MAX_DAYS: i64 = 10;
+/// Максимальный размер страницы для /iss/history
+pub const HISTORY_MAX_LIMIT: i64 = 500;
+/// Максимальное окно для /iss/trend (в часах)
+pub const TREND_MAX_HOURS: i64 = 168;
+/// Ограничение числа замеров, загружаемых для анализа тренда (берутся самые новые,
+/// обрезка окна отмечается в ответе - truncated/covered_from)
+const TREND_MAX_SAMPLES: i64 = 20000;
+/// Минимальный интервал опроса отслеживаемого объекта (в секундах)
+pub const POLL_MIN_SECONDS: i64 = 10;
+/// Максимальная длина имени отслеживаемого объекта
+pub const OBJECT_NAME_MAX_LEN: usize = 200;
+
+/// Сервис для работы с данными МКС и прочих отслеживаемых объектов (по NORAD ID)
 pub struct IssService {
     repo: IssRepo,
+    objects: TrackedObjectRepo,
     client: IssClient,
+    // Провайдеры позиции в порядке приоритета (failover)
+    providers: Vec<Arc<dyn IssPositionProvider>>,
+    cache_repo: CacheRepo,
+    geofences: Arc<GeofenceService>,
+    // Рассылка новых позиций подписчикам /iss/stream и /ws/iss
+    positions: broadcast::Sender<IssPosition>,
 }
 
 impl IssService {
-    pub fn new(repo: IssRepo, client: IssClient) -> Self {
-        Self { repo, client }
+    pub fn new(
+        repo: IssRepo,
+        objects: TrackedObjectRepo,
+        client: IssClient,
+        providers: Vec<Arc<dyn IssPositionProvider>>,
+        cache_repo: CacheRepo,
+        geofences: Arc<GeofenceService>,
+        stream_capacity: usize,
+    ) -> Self {
+        let (positions, _) = broadcast::channel(stream_capacity.max(1));
+        Self { repo, objects, client, providers, cache_repo, geofences, positions }
     }
 
-    /// Получить последнюю позицию МКС
-    pub async fn get_last_position(&self) -> Result<Option<IssPosition>, ApiError> {
-        self.repo.get_last().await
+    /// Подписка на новые позиции всех объектов (по мере сохранения)
+    pub fn subscribe(&self) -> broadcast::Receiver<IssPosition> {
+        self.positions.subscribe()
     }
 
-    /// Триггер ручной загрузки данных МКС
-    pub async fn fetch_and_store(&self) -> Result<IssPosition, ApiError> {
-        let payload = self.client.fetch_current_position().await?;
-        let source_url = "https://api.wheretheiss.at/v1/satellites/25544";
-        
-        let id = self.repo.insert(source_url, payload.clone()).await?;
+    /// Реестр отслеживаемых объектов
+    pub async fn list_objects(&self, enabled_only: bool) -> Result<Vec<TrackedObject>, ApiError> {
+        self.objects.list(enabled_only).await
+    }
+
+    /// Объект из реестра; NotFound, если он не отслеживается
+    pub async fn tracked_object(&self, norad_id: i32) -> Result<TrackedObject, ApiError> {
+        self.objects
+            .get(norad_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Object with NORAD ID {} is not tracked", norad_id)))
+    }
+
+    /// Добавить объект в реестр или обновить его
+    pub async fn upsert_object(&self, mut input: TrackedObjectInput) -> Result<TrackedObject, ApiError> {
+        input.name = input.name.trim().to_string();
+        if input.norad_id <= 0 {
+            return Err(ApiError::ValidationError("'norad_id' must be positive".to_string()));
+        }
+        if input.name.is_empty() || input.name.chars().count() > OBJECT_NAME_MAX_LEN {
+            return Err(ApiError::ValidationError(format!(
+                "'name' must be 1 to {} characters", OBJECT_NAME_MAX_LEN
+            )));
+        }
+        if let Some(poll) = input.poll_every_seconds {
+            if poll < POLL_MIN_SECONDS {
+                return Err(ApiError::ValidationError(format!(
+                    "'poll_every_seconds' must be at least {}", POLL_MIN_SECONDS
+                )));
+            }
+        }
+        let object = self.objects.upsert(&input).await?;
+
+        // Без TLE новый объект не опросить до следующего цикла TLE-планировщика
+        if object.enabled {
+            if let Err(e) = self.refresh_tle(object.norad_id).await {
+                warn!("Initial TLE load failed for NORAD {}: {}", object.norad_id, e);
+            }
+        }
+        Ok(object)
+    }
+
+    /// Убрать объект из реестра (сохранённые позиции остаются)
+    pub async fn delete_object(&self, norad_id: i32) -> Result<(), ApiError> {
+        if !self.objects.delete(norad_id).await? {
+            return Err(ApiError::NotFound(format!("Object with NORAD ID {} is not tracked", norad_id)));
+        }
+        Ok(())
+    }
+
+    /// Получить последнюю позицию объекта
+    pub async fn get_last_position(&self, norad_id: i32) -> Result<Option<IssPosition>, ApiError> {
+        self.repo.get_last(norad_id).await
+    }
+
+    /// Получить страницу истории позиций (от новых к старым)
+    pub async fn get_history(
+        &self,
+        norad_id: i32,
+        limit: i64,
+        cursor: Option<i64>,
+    ) -> Result<IssHistory, ApiError> {
+        if !(1..=HISTORY_MAX_LIMIT).contains(&limit) {
+            return Err(ApiError::ValidationError(format!(
+                "'limit' must be between 1 and {}", HISTORY_MAX_LIMIT
+            )));
+        }
+
+        // Берём на одну запись больше, чтобы понять, есть ли следующая страница
+        let mut items = self.repo.get_last_n(norad_id, limit + 1, cursor).await?;
+        let has_more = items.len() as i64 > limit;
+        items.truncate(limit as usize);
+        let next_cursor = if has_more { items.last().map(|p| p.id) } else { None };
+
+        Ok(IssHistory {
+            items,
+            limit,
+            next_cursor,
+        })
+    }
+
+    /// Опросить провайдеров объекта по порядку; первый успешный ответ - результат
+    async fn fetch_from_providers(
+        &self,
+        norad_id: i32,
+    ) -> Result<(&dyn IssPositionProvider, ProviderPosition), ApiError> {
+        let mut failures = Vec::new();
+        for provider in self.providers.iter().filter(|p| p.supports(norad_id)) {
+            match provider.fetch_position(norad_id).await {
+                Ok(position) => {
+                    if !failures.is_empty() {
+                        warn!("NORAD {} position served by fallback provider '{}'", norad_id, provider.name());
+                    }
+                    return Ok((provider.as_ref(), position));
+                }
+                Err(e) => {
+                    warn!("Provider '{}' failed for NORAD {}: {}", provider.name(), norad_id, e);
+                    failures.push(format!("{}: {}", provider.name(), e));
+                }
+            }
+        }
+
+        Err(ApiError::UpstreamError {
+            code: "ALL_PROVIDERS_FAILED".to_string(),
+            message: if failures.is_empty() {
+                format!("No configured provider supports NORAD {}", norad_id)
+            } else {
+                failures.join("; ")
+            },
+        })
+    }
+
+    /// Загрузка и сохранение текущей позиции объекта
+    pub async fn fetch_and_store(&self, norad_id: i32) -> Result<IssPosition, ApiError> {
+        let (provider, mut fetched) = self.fetch_from_providers(norad_id).await?;
+        if fetched.visibility.is_none() {
+            fetched.visibility = self.computed_visibility(norad_id, &fetched).await;
+        }
+
+        // Предыдущий замер нужен для определения пересечения границ геозон
+        let previous = self.repo.get_last(norad_id).await?;
+
+        let id = self
+            .repo
+            .insert(
+                norad_id,
+                &provider.source_url(norad_id),
+                provider.name(),
+                fetched.to_payload(provider.name()),
+            )
+            .await?;
         
         // Получаем только что вставленную запись
-        self.repo.get_last().await?
-            .ok_or_else(|| ApiError::InternalError("Failed to retrieve inserted record".to_string()))
-    }
-
-    /// Вычислить тренд движения МКС
-    pub async fn calculate_trend(&self) -> Result<IssTrend, ApiError> {
-        let positions = self.repo.get_last_n(2).await?;
-
-        if positions.len() < 2 {
-            return Ok(IssTrend {
-                movement: false,
-                delta_km: 0.0,
-                dt_sec: 0.0,
-                velocity_kmh: None,
-                from_time: None,
-                to_time: None,
-                from_lat: None,
-                from_lon: None,
-                to_lat: None,
-                to_lon: None,
-            });
-        }
-
-        let recent = &positions[0];
-        let previous = &positions[1];
-
-        let mut delta_km = 0.0;
-        let mut movement = false;
-
-        if let (Some(lat1), Some(lon1), Some(lat2), Some(lon2)) = (
-            previous.latitude,
-            previous.longitude,
-            recent.latitude,
-            recent.longitude,
-        ) {
-            delta_km = haversine_km(lat1, lon1, lat2, lon2);
-            movement = delta_km > 0.1;
-        }
-
-        let dt_sec = (recent.fetched_at - previous.fetched_at).num_milliseconds() as f64 / 1000.0;
-
-        Ok(IssTrend {
-            movement,
-            delta_km,
-            dt_sec,
-            velocity_kmh: recent.velocity,
-            from_time: Some(previous.fetched_at),
-            to_time: Some(recent.fetched_at),
-            from_lat: previous.latitude,
-            from_lon: previous.longitude,
-            to_lat: recent.latitude,
-            to_lon: recent.longitude,
+        let position = self.repo.get_by_id(id).await?
+            .ok_or_else(|| ApiError::InternalError("Failed to retrieve inserted record".to_string()))?;
+
+        // Геозоны описывают пролёты МКС; ошибка не должна срывать сохранение позиции
+        if norad_id == ISS_NORAD_ID {
+            if let Err(e) = self.geofences.evaluate(previous.as_ref(), &position).await {
+                warn!("Geofence evaluation failed for position {}: {}", position.id, e);
+            }
+        }
+
+        // Ошибка send означает лишь отсутствие подписчиков
+        let _ = self.positions.send(position.clone());
+
+        Ok(position)
+    }
+
+    /// Освещённость для провайдеров без visibility (open-notify): по высоте из ответа,
+    /// без неё - по SGP4; ошибка расчёта оставляет поле пустым
+    async fn computed_visibility(&self, norad_id: i32, fetched: &ProviderPosition) -> Option<String> {
+        let sunlit = match fetched.altitude {
+            Some(altitude_km) => Some(is_sunlit_geodetic(
+                &Geodetic { latitude: fetched.latitude, longitude: fetched.longitude, altitude_km },
+                fetched.timestamp,
+            )),
+            None => match self.current_tle(norad_id).await.and_then(|(tle, _)| {
+                Ok(Sgp4::new(&tle)?.propagate_at(fetched.timestamp)?)
+            }) {
+                Ok(state) => Some(is_sunlit(state.position, fetched.timestamp)),
+                Err(e) => {
+                    warn!("Cannot compute visibility for NORAD {}: {}", norad_id, e);
+                    None
+                }
+            },
+        };
+        sunlit.map(|s| visibility_label(s).to_string())
+    }
+
+    /// Освещённость объекта на момент `at`: по SGP4 или по переданным координатам
+    pub async fn illumination(
+        &self,
+        norad_id: i32,
+        at: Option<DateTime<Utc>>,
+        coordinates: Option<Geodetic>,
+    ) -> Result<IssIllumination, ApiError> {
+        let at = at.unwrap_or_else(Utc::now);
+
+        let (geo, sunlit, source, tle) = match coordinates {
+            Some(geo) => {
+                if !(-90.0..=90.0).contains(&geo.latitude) {
+                    return Err(ApiError::ValidationError("'lat' must be between -90 and 90".to_string()));
+                }
+                if !(-180.0..=180.0).contains(&geo.longitude) {
+                    return Err(ApiError::ValidationError("'lon' must be between -180 and 180".to_string()));
+                }
+                if !(0.0..=100_000.0).contains(&geo.altitude_km) {
+                    return Err(ApiError::ValidationError("'alt' must be between 0 and 100000 km".to_string()));
+                }
+                (geo, is_sunlit_geodetic(&geo, at), "coordinates", None)
+            }
+            None => {
+                let (tle, info) = self.current_tle(norad_id).await?;
+                check_tle_distance(&tle, at)?;
+                let state = Sgp4::new(&tle)?.propagate_at(at)?;
+                (subpoint(&state, at), is_sunlit(state.position, at), "tle", Some(info))
+            }
+        };
+
+        Ok(IssIllumination {
+            norad_id,
+            at,
+            latitude: geo.latitude,
+            longitude: geo.longitude,
+            altitude: geo.altitude_km,
+            sunlit,
+            visibility: visibility_label(sunlit).to_string(),
+            sun_elevation: solar_elevation(geo.latitude, geo.longitude, at),
+            subsolar: subsolar_point(at),
+            source: source.to_string(),
+            tle,
+        })
+    }
+
+    /// Получить позиции объекта за период с пагинацией и прореживанием
+    pub async fn get_range(
+        &self,
+        norad_id: i32,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: i64,
+        offset: i64,
+        step_sec: Option<i64>,
+    ) -> Result<IssRange, ApiError> {
+        let to = to.unwrap_or_else(Utc::now);
+        let from = from.unwrap_or(to - Duration::hours(24));
+
+        if from > to {
+            return Err(ApiError::ValidationError("'from' must be before or equal to 'to'".to_string()));
+        }
+        if to - from > Duration::days(RANGE_MAX_DAYS) {
+            return Err(ApiError::ValidationError(format!(
+                "Range cannot exceed {} days", RANGE_MAX_DAYS
+            )));
+        }
+        if !(1..=RANGE_MAX_LIMIT).contains(&limit) {
+            return Err(ApiError::ValidationError(format!(
+                "'limit' must be between 1 and {}", RANGE_MAX_LIMIT
+            )));
+        }
+        if offset < 0 {
+            return Err(ApiError::ValidationError("'offset' must be non-negative".to_string()));
+        }
+        if let Some(step) = step_sec {
+            if !(1..=86400).contains(&step) {
+                return Err(ApiError::ValidationError("'step' must be between 1 and 86400 seconds".to_string()));
+            }
+        }
+
+        let items = self.repo.get_by_time_range(norad_id, from, to, step_sec, limit, offset).await?;
+        let total = self.repo.count_by_time_range(norad_id, from, to, step_sec).await?;
+
+        Ok(IssRange {
+            from,
+            to,
+            items,
+            total,
+            limit,
+            offset,
+            step_sec,
+        })
+    }
+
+    /// Загрузить свежий TLE объекта и сохранить в space_cache
+    pub async fn refresh_tle(&self, norad_id: i32) -> Result<TleInfo, ApiError> {
+        let payload = self.client.fetch_tle(norad_id).await?;
+        // Проверяем TLE до записи в кэш, чтобы не затереть рабочий набор мусором
+        let tle = parse_tle_for(&payload, norad_id)?;
+        self.cache_repo.insert(&tle_cache_source(norad_id), payload).await?;
+        Ok(tle_info(&tle, Utc::now()))
+    }
+
+    /// Текущий TLE объекта: последний из кэша, при пустом кэше - загрузка
+    async fn current_tle(&self, norad_id: i32) -> Result<(Tle, TleInfo), ApiError> {
+        let source = tle_cache_source(norad_id);
+        let (payload, fetched_at) = match self.cache_repo.get_latest(&source).await? {
+            Some(entry) => (entry.payload, entry.fetched_at),
+            None => {
+                let payload = self.client.fetch_tle(norad_id).await?;
+                parse_tle_for(&payload, norad_id)?;
+                self.cache_repo.insert(&source, payload.clone()).await?;
+                (payload, Utc::now())
+            }
+        };
+
+        let tle = parse_tle_payload(&payload)?;
+        let info = tle_info(&tle, fetched_at);
+        Ok((tle, info))
+    }
+
+    /// Прогноз положения объекта на момент `at` (по умолчанию - сейчас)
+    pub async fn predict(&self, norad_id: i32, at: Option<DateTime<Utc>>) -> Result<IssPrediction, ApiError> {
+        let at = at.unwrap_or_else(Utc::now);
+        let (tle, info) = self.current_tle(norad_id).await?;
+        check_tle_distance(&tle, at)?;
+
+        let sgp4 = Sgp4::new(&tle)?;
+        let point = IssTrackPoint::propagate(&sgp4, at)?;
+
+        Ok(IssPrediction {
+            point,
+            tle_age_hours: tle.age_hours(at),
+            tle: info,
+        })
+    }
+
+    /// Расчётная трасса объекта с шагом `step_sec` (прошлое между опросами или будущее)
+    pub async fn track(
+        &self,
+        norad_id: i32,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        step_sec: i64,
+    ) -> Result<IssTrack, ApiError> {
+        let from = from.unwrap_or_else(Utc::now);
+        let to = to.unwrap_or(from + Duration::minutes(93));
+
+        if from > to {
+            return Err(ApiError::ValidationError("'from' must be before or equal to 'to'".to_string()));
+        }
+        if !(1..=3600).contains(&step_sec) {
+            return Err(ApiError::ValidationError("'step' must be between 1 and 3600 seconds".to_string()));
+        }
+        if (to - from).num_seconds() / step_sec + 1 > TRACK_MAX_POINTS {
+            return Err(ApiError::ValidationError(format!(
+                "Track cannot exceed {} points, increase 'step' or shorten the range", TRACK_MAX_POINTS
+            )));
+        }
+
+        let (tle, info) = self.current_tle(norad_id).await?;
+        check_tle_distance(&tle, from)?;
+        check_tle_distance(&tle, to)?;
+        let sgp4 = Sgp4::new(&tle)?;
+
+        let mut points = Vec::new();
+        let mut at = from;
+        while at <= to {
+            points.push(IssTrackPoint::propagate(&sgp4, at)?);
+            at += Duration::seconds(step_sec);
+        }
+
+        Ok(IssTrack {
+            from,
+            to,
+            step_sec,
+            points,
+            tle: info,
         })
     }
+
+    /// Наземная трасса для экспорта, разбитая по антимеридиану
+    pub async fn ground_track(
+        &self,
+        norad_id: i32,
+        source: TrackSource,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        step_sec: Option<i64>,
+    ) -> Result<Vec<Vec<TrackSample>>, ApiError> {
+        let samples: Vec<TrackSample> = match source {
+            TrackSource::Stored => {
+                // Для сохранённых замеров окно по умолчанию - последние сутки
+                let to = to.unwrap_or_else(Utc::now);
+                let from = from.unwrap_or(to - Duration::hours(24));
+                let mut page = self.get_range(norad_id, Some(from), Some(to), RANGE_MAX_LIMIT, 0, step_sec).await?;
+                if page.total > TRACK_MAX_POINTS {
+                    return Err(ApiError::ValidationError(format!(
+                        "Track cannot exceed {} points, increase 'step' or shorten the range", TRACK_MAX_POINTS
+                    )));
+                }
+                // Выбираем все страницы, чтобы трасса не обрезалась на RANGE_MAX_LIMIT
+                let mut samples: Vec<TrackSample> = page.items.iter().filter_map(TrackSample::from_position).collect();
+                let mut offset = page.items.len() as i64;
+                while !page.items.is_empty() && offset < page.total {
+                    page = self.get_range(norad_id, Some(from), Some(to), RANGE_MAX_LIMIT, offset, step_sec).await?;
+                    samples.extend(page.items.iter().filter_map(TrackSample::from_position));
+                    offset += page.items.len() as i64;
+                }
+                samples
+            }
+            TrackSource::Propagated => self
+                .track(norad_id, from, to, step_sec.unwrap_or(60))
+                .await?
+                .points
+                .iter()
+                .map(TrackSample::from)
+                .collect(),
+        };
+
+        Ok(split_antimeridian(&samples))
+    }
+
+    /// Пролёты объекта над наблюдателем на ближайшие `days` суток
+    pub async fn passes(&self, norad_id: i32, observer: Observer, days: i64) -> Result<IssPasses, ApiError> {
+        if !(-90.0..=90.0).contains(&observer.latitude) {
+            return Err(ApiError::ValidationError("'lat' must be between -90 and 90".to_string()));
+        }
+        if !(-180.0..=180.0).contains(&observer.longitude) {
+            return Err(ApiError::ValidationError("'lon' must be between -180 and 180".to_string()));
+        }
+        if !(-500.0..=9000.0).contains(&observer.altitude_m) {
+            return Err(ApiError::ValidationError("'alt' must be between -500 and 9000 meters".to_string()));
+        }
+        if !(1..=PASSES_MAX_DAYS).contains(&days) {
+            return Err(ApiError::ValidationError(format!(
+                "'days' must be between 1 and {}", PASSES_MAX_DAYS
+            )));
+        }
+
+        let from = Utc::now();
+        let to = from + Duration::days(days);
+        let (tle, info) = self.current_tle(norad_id).await?;
+        check_tle_distance(&tle, to)?;
+        let sgp4 = Sgp4::new(&tle)?;
+
+        let geodetic = Geodetic {
+            latitude: observer.latitude,
+            longitude: observer.longitude,
+            altitude_km: observer.altitude_m / 1000.0,
+        };
+        let passes = find_passes(&sgp4, &geodetic, from, to)?;
+
+        Ok(IssPasses {
+            observer,
+            from,
+            to,
+            passes,
+            tle: info,
+        })
+    }
+
+    /// Бэкфилл типизированных колонок для записей, сохранённых до их появления
+    pub async fn backfill_typed_columns(&self, batch: i64) -> Result<u64, ApiError> {
+        let mut after_id = 0;
+        let mut total = 0u64;
+
+        loop {
+            let ids = self.repo.backfill_typed_columns(after_id, batch).await?;
+            match ids.iter().max() {
+                Some(&max_id) => {
+                    after_id = max_id;
+                    total += ids.len() as u64;
+                }
+                None => break,
+            }
+        }
+
+        Ok(total)
+    }
+
+    /// Вычислить тренд движения объекта за последние `hours` часов
+    pub async fn calculate_trend(&self, norad_id: i32, hours: i64) -> Result<IssTrend, ApiError> {
+        if !(1..=TREND_MAX_HOURS).contains(&hours) {
+            return Err(ApiError::ValidationError(format!(
+                "'hours' must be between 1 and {}", TREND_MAX_HOURS
+            )));
+        }
+
+        let window_to = Utc::now();
+        let window_from = window_to - Duration::hours(hours);
+        // Лишний замер сверх лимита показывает, что окно обрезано
+        let mut window = self
+            .repo
+            .get_latest_in_range(norad_id, window_from, window_to, TREND_MAX_SAMPLES + 1)
+            .await?;
+        let truncated = window.len() as i64 > TREND_MAX_SAMPLES;
+        if truncated {
+            window.remove(0);
+        }
+        let covered_from = window.first().map(|p| p.fetched_at);
+
+        let last_two = self.repo.get_last_n(norad_id, 2, None).await?;
+
+        let mut trend = IssTrend {
+            movement: false,
+            delta_km: 0.0,
+            dt_sec: 0.0,
+            velocity_kmh: None,
+            from_time: None,
+            to_time: None,
+            from_lat: None,
+            from_lon: None,
+            to_lat: None,
+            to_lon: None,
+            window_hours: hours,
+            window_from,
+            window_to,
+            samples: window.len(),
+            truncated,
+            covered_from,
+            total_distance_km: 0.0,
+            velocity_stats: None,
+            altitude_stats: None,
+            altitude_rate_km_h: None,
+            gap_threshold_sec: None,
+            gaps: Vec::new(),
+        };
+
+        if last_two.len() == 2 {
+            let recent = &last_two[0];
+            let previous = &last_two[1];
+
+            if let (Some(lat1), Some(lon1), Some(lat2), Some(lon2)) = (
+                previous.latitude,
+                previous.longitude,
+                recent.latitude,
+                recent.longitude,
+            ) {
+                trend.delta_km = haversine_km(lat1, lon1, lat2, lon2);
+                trend.movement = trend.delta_km > 0.1;
+            }
+
+            trend.dt_sec = seconds_between(previous.fetched_at, recent.fetched_at);
+            trend.velocity_kmh = recent.velocity;
+            trend.from_time = Some(previous.fetched_at);
+            trend.to_time = Some(recent.fetched_at);
+            trend.from_lat = previous.latitude;
+            trend.from_lon = previous.longitude;
+            trend.to_lat = recent.latitude;
+            trend.to_lon = recent.longitude;
+        }
+
+        // Окно приходит по возрастанию fetched_at
+        trend.total_distance_km = window
+            .windows(2)
+            .filter_map(|w| match (w[0].latitude, w[0].longitude, w[1].latitude, w[1].longitude) {
+                (Some(lat1), Some(lon1), Some(lat2), Some(lon2)) => Some(haversine_km(lat1, lon1, lat2, lon2)),
+                _ => None,
+            })
+            .sum();
+
+        let velocities: Vec<f64> = window.iter().filter_map(|p| p.velocity).collect();
+        let altitudes: Vec<f64> = window.iter().filter_map(|p| p.altitude).collect();
+        trend.velocity_stats = IssStats::from_values(&velocities);
+        trend.altitude_stats = IssStats::from_values(&altitudes);
+
+        // Время отсчитывается от первого замера анализа, а не от начала окна
+        let origin = covered_from.unwrap_or(window_from);
+        let altitude_points: Vec<(f64, f64)> = window
+            .iter()
+            .filter_map(|p| p.altitude.map(|alt| (seconds_between(origin, p.fetched_at) / 3600.0, alt)))
+            .collect();
+        trend.altitude_rate_km_h = linear_slope(&altitude_points);
+
+        let mut intervals: Vec<f64> = window
+            .windows(2)
+            .map(|w| seconds_between(w[0].fetched_at, w[1].fetched_at))
+            .collect();
+        if !intervals.is_empty() {
+            intervals.sort_by(|a, b| a.total_cmp(b));
+            let threshold = intervals[intervals.len() / 2] * 3.0;
+            trend.gap_threshold_sec = Some(threshold);
+            trend.gaps = window
+                .windows(2)
+                .filter_map(|w| {
+                    let duration_sec = seconds_between(w[0].fetched_at, w[1].fetched_at);
+                    (duration_sec > threshold).then(|| IssGap {
+                        from: w[0].fetched_at,
+                        to: w[1].fetched_at,
+                        duration_sec,
+                    })
+                })
+                .collect();
+        }
+
+        Ok(trend)
+    }
+}
+
+/// TLE из ответа провайдера: {"header"|"name", "line1", "line2"}
+pub(crate) fn parse_tle_payload(payload: &serde_json::Value) -> Result<Tle, ApiError> {
+    let line = |key: &str| {
+        payload.get(key).and_then(|v| v.as_str()).ok_or_else(|| ApiError::UpstreamError {
+            code: "INVALID_TLE".to_string(),
+            message: format!("TLE payload has no '{}'", key),
+        })
+    };
+    let name = payload
+        .get("header")
+        .or_else(|| payload.get("name"))
+        .and_then(|v| v.as_str());
+
+    Tle::parse(name, line("line1")?, line("line2")?).map_err(|e| ApiError::UpstreamError {
+        code: "INVALID_TLE".to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// TLE с проверкой, что он относится к запрошенному объекту
+fn parse_tle_for(payload: &serde_json::Value, norad_id: i32) -> Result<Tle, ApiError> {
+    let tle = parse_tle_payload(payload)?;
+    if tle.norad_id as i64 != norad_id as i64 {
+        return Err(ApiError::UpstreamError {
+            code: "INVALID_TLE".to_string(),
+            message: format!("TLE is for NORAD {}, expected {}", tle.norad_id, norad_id),
+        });
+    }
+    Ok(tle)
+}
+
+fn tle_info(tle: &Tle, fetched_at: DateTime<Utc>) -> TleInfo {
+    TleInfo {
+        name: tle.name.clone(),
+        norad_id: tle.norad_id,
+        epoch: tle.epoch,
+        line1: tle.line1.clone(),
+        line2: tle.line2.clone(),
+        fetched_at,
+    }
+}
+
+fn check_tle_distance(tle: &Tle, at: DateTime<Utc>) -> Result<(), ApiError> {
+    if tle.age_hours(at).abs() > (PREDICT_MAX_DAYS * 24) as f64 {
+        return Err(ApiError::ValidationError(format!(
+            "Time must be within {} days of the TLE epoch {}", PREDICT_MAX_DAYS, tle.epoch
+        )));
+    }
+    Ok(())
 }
 
-/// Формула гаверсинуса для вычисления расстояния между двумя точками на сфере
-fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
-    let rlat1 = lat1.to_radians();
-    let rlat2 = lat2.to_radians();
-    let dlat = (lat2 - lat1).to_radians();
-    let dlon = (lon2 - lon1).to_radians();
-    
-    let a = (dlat / 2.0).sin().powi(2) 
-        + rlat1.cos() * rlat2.cos() * (dlon / 2.0).sin().powi(2);
-    let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
-    
-    6371.0 * c // Радиус Земли в км
+fn seconds_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_milliseconds() as f64 / 1000.0
 }
@@ -1,9 +1,18 @@
 // Services layer - бизнес-логика
+pub mod altitude_service;
 pub mod iss_service;
+pub mod geofence_service;
+pub mod osdr_export;
 pub mod osdr_service;
 pub mod space_service;
 pub mod scheduler;
+pub mod sync_run_service;
+pub mod tle_provider;
 
+pub use altitude_service::AltitudeService;
 pub use iss_service::IssService;
+pub use geofence_service::GeofenceService;
 pub use osdr_service::OsdrService;
 pub use space_service::SpaceService;
+pub use sync_run_service::SyncRunService;
+pub use tle_provider::TlePropagationProvider;
@@ -0,0 +1,443 @@
+// Кодирование выгрузки OSDR в CSV, XLSX и JSON Lines
+use crate::domain::{
+    osdr::{OsdrExportFormat, OsdrExportRow, OSDR_EXPORT_COLUMNS},
+    ApiError,
+};
+use rust_xlsxwriter::{Color, ExcelDateTime, Format, Workbook, XlsxError};
+use std::io::{self, Seek, SeekFrom, Write};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Строк данных на листе XLSX (без заголовка)
+pub const OSDR_EXPORT_XLSX_MAX_ROWS: u32 = 1_048_575;
+/// Строк выгрузки в очереди между запросом к БД и кодированием; с той же частотой
+/// закодированные CSV и JSON Lines уходят клиенту
+pub const OSDR_EXPORT_ROW_BUFFER: usize = 256;
+/// Максимальная длина строки в ячейке Excel - в UTF-16 единицах, не в символах
+const XLSX_MAX_STRING_UTF16: usize = 32_767;
+/// Метка в конце обрезанной ячейки, входит в лимит
+const XLSX_TRUNCATED_MARKER: &str = "…[truncated]";
+
+/// Ширина колонок XLSX, в порядке OSDR_EXPORT_COLUMNS
+const XLSX_COLUMN_WIDTHS: [f64; 11] = [8.0, 14.0, 50.0, 12.0, 20.0, 30.0, 30.0, 30.0, 20.0, 20.0, 60.0];
+
+/// Закодировать строки из `rows` и отправить в `out`. Блокирующая (XLSX пишет лист во временный
+/// файл и упаковывает архив) - вызывается из spawn_blocking. Отключение клиента - не ошибка.
+pub fn encode(
+    format: OsdrExportFormat,
+    mut rows: mpsc::Receiver<Result<OsdrExportRow, ApiError>>,
+    out: mpsc::Sender<Result<Vec<u8>, io::Error>>,
+) -> Result<usize, ApiError> {
+    let mut sink = ChunkSender::new(out);
+    let mut exported = 0usize;
+
+    let mut encode_rows = || {
+        let mut writer = OsdrExportWriter::new(format)?;
+        while let Some(row) = rows.blocking_recv() {
+            writer.write(&row?)?;
+            exported += 1;
+            // is_multiple_of стабилен только с Rust 1.87, образ собирается на 1.83
+            #[allow(unknown_lints, clippy::manual_is_multiple_of)]
+            let flush = exported % OSDR_EXPORT_ROW_BUFFER == 0;
+            if flush {
+                sink.write_all(&writer.take_chunk()).map_err(export_io_error)?;
+                sink.send_pending().map_err(export_io_error)?;
+            }
+        }
+        writer.finish(&mut sink)?;
+        sink.send_pending().map_err(export_io_error)
+    };
+
+    match encode_rows() {
+        Err(_) if sink.closed => Ok(exported),
+        result => result.map(|_| exported),
+    }
+}
+
+/// Потоковая запись выгрузки. CSV и JSON Lines кодируются построчно и забираются
+/// через take_chunk; XLSX пишется на лист в режиме constant memory (строки уходят
+/// во временный файл) и упаковывается в finish.
+pub enum OsdrExportWriter {
+    Csv(Vec<u8>),
+    Jsonl(Vec<u8>),
+    Xlsx(Box<XlsxSheet>),
+}
+
+pub struct XlsxSheet {
+    workbook: Workbook,
+    date_format: Format,
+    rows: u32,
+}
+
+impl OsdrExportWriter {
+    /// Новая выгрузка с заголовком (CSV, XLSX)
+    pub fn new(format: OsdrExportFormat) -> Result<Self, ApiError> {
+        match format {
+            OsdrExportFormat::Csv => {
+                let mut buf = Vec::new();
+                write_csv_record(&mut buf, OSDR_EXPORT_COLUMNS)?;
+                Ok(Self::Csv(buf))
+            }
+            OsdrExportFormat::Jsonl => Ok(Self::Jsonl(Vec::new())),
+            OsdrExportFormat::Xlsx => XlsxSheet::new().map(|sheet| Self::Xlsx(Box::new(sheet))).map_err(xlsx_error),
+        }
+    }
+
+    pub fn write(&mut self, row: &OsdrExportRow) -> Result<(), ApiError> {
+        match self {
+            Self::Csv(buf) => write_csv_record(buf, row.text_fields()),
+            Self::Jsonl(buf) => {
+                serde_json::to_writer(&mut *buf, row)
+                    .map_err(|e| ApiError::InternalError(format!("JSON export failed: {}", e)))?;
+                buf.push(b'\n');
+                Ok(())
+            }
+            Self::Xlsx(sheet) => sheet.write(row),
+        }
+    }
+
+    /// Закодированные, но ещё не отданные байты (для XLSX всегда пусто до finish)
+    pub fn take_chunk(&mut self) -> Vec<u8> {
+        match self {
+            Self::Csv(buf) | Self::Jsonl(buf) => std::mem::take(buf),
+            Self::Xlsx(_) => Vec::new(),
+        }
+    }
+
+    /// Дописать остаток выгрузки в `out`; для XLSX - архив целиком
+    pub fn finish<W: Write + Seek + Send>(self, out: &mut W) -> Result<(), ApiError> {
+        match self {
+            Self::Csv(buf) | Self::Jsonl(buf) => out.write_all(&buf).map_err(export_io_error),
+            Self::Xlsx(mut sheet) => sheet.workbook.save_to_writer(out).map_err(xlsx_error),
+        }
+    }
+}
+
+impl XlsxSheet {
+    fn new() -> Result<Self, XlsxError> {
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet_with_constant_memory();
+        worksheet.set_name("OSDR")?;
+
+        let header_format = Format::new()
+            .set_bold()
+            .set_background_color(Color::RGB(0x4472C4))
+            .set_font_color(Color::White);
+
+        for (col, (header, width)) in OSDR_EXPORT_COLUMNS.iter().zip(XLSX_COLUMN_WIDTHS).enumerate() {
+            worksheet.set_column_width(col as u16, width)?;
+            worksheet.write_string_with_format(0, col as u16, *header, &header_format)?;
+        }
+        worksheet.set_freeze_panes(1, 0)?;
+
+        Ok(Self {
+            workbook,
+            date_format: Format::new().set_num_format("yyyy-mm-dd hh:mm:ss"),
+            rows: 0,
+        })
+    }
+
+    /// Строка с типизированными ячейками: id - число, даты - дата/время Excel, остальное - текст
+    fn write(&mut self, row: &OsdrExportRow) -> Result<(), ApiError> {
+        if self.rows >= OSDR_EXPORT_XLSX_MAX_ROWS {
+            return Err(ApiError::ValidationError(format!(
+                "XLSX export is limited to {} rows, use csv or jsonl", OSDR_EXPORT_XLSX_MAX_ROWS
+            )));
+        }
+        self.rows += 1;
+        let r = self.rows;
+        let date_format = &self.date_format;
+        let worksheet = self.workbook.worksheet_from_index(0).map_err(xlsx_error)?;
+
+        let mut truncated = Vec::new();
+        let mut cell = |col: usize, value: &str| match truncate_cell(value) {
+            Some(cut) => {
+                truncated.push(OSDR_EXPORT_COLUMNS[col]);
+                cut
+            }
+            None => value.to_string(),
+        };
+        let dataset_id = cell(1, row.dataset_id.as_deref().unwrap_or_default());
+        let title = cell(2, row.title.as_deref().unwrap_or_default());
+        let status = cell(3, row.status.as_deref().unwrap_or_default());
+        let mission = cell(4, row.mission.as_deref().unwrap_or_default());
+        let organisms = cell(5, &row.organisms.join("; "));
+        let assay_types = cell(6, &row.assay_types.join("; "));
+        let factors = cell(7, &row.factors.join("; "));
+        let description = cell(10, row.description.as_deref().unwrap_or_default());
+        if !truncated.is_empty() {
+            warn!(
+                "XLSX export: dataset {} (id {}) cells truncated to {} UTF-16 units: {}",
+                row.dataset_id.as_deref().unwrap_or("-"),
+                row.id,
+                XLSX_MAX_STRING_UTF16,
+                truncated.join(", ")
+            );
+        }
+
+        let mut write = || -> Result<(), XlsxError> {
+            worksheet.write_number(r, 0, row.id as f64)?;
+            worksheet.write_string(r, 1, &dataset_id)?;
+            worksheet.write_string(r, 2, &title)?;
+            worksheet.write_string(r, 3, &status)?;
+            worksheet.write_string(r, 4, &mission)?;
+            worksheet.write_string(r, 5, &organisms)?;
+            worksheet.write_string(r, 6, &assay_types)?;
+            worksheet.write_string(r, 7, &factors)?;
+            if let Some(updated_at) = row.updated_at {
+                let datetime = ExcelDateTime::from_timestamp(updated_at.timestamp())?;
+                worksheet.write_datetime_with_format(r, 8, &datetime, date_format)?;
+            }
+            let datetime = ExcelDateTime::from_timestamp(row.inserted_at.timestamp())?;
+            worksheet.write_datetime_with_format(r, 9, &datetime, date_format)?;
+            worksheet.write_string(r, 10, &description)?;
+            Ok(())
+        };
+
+        write().map_err(xlsx_error)
+    }
+}
+
+/// Приёмник выгрузки поверх канала ответа. Seek нужен Workbook::save_to_writer: ZipWriter
+/// дописывает заголовок записи архива после её данных. Поэтому байты копятся в буфере, пока
+/// ZipWriter после такой правки не вернётся в конец - всё до этого места окончательно
+/// и уходит в канал. В памяти держится только текущая запись архива.
+struct ChunkSender {
+    out: mpsc::Sender<Result<Vec<u8>, io::Error>>,
+    /// Позиция первого байта буфера (всё до неё отправлено)
+    sent: u64,
+    buf: Vec<u8>,
+    pos: u64,
+    /// Клиент отключился
+    closed: bool,
+}
+
+impl ChunkSender {
+    fn new(out: mpsc::Sender<Result<Vec<u8>, io::Error>>) -> Self {
+        Self { out, sent: 0, buf: Vec::new(), pos: 0, closed: false }
+    }
+
+    fn end(&self) -> u64 {
+        self.sent + self.buf.len() as u64
+    }
+
+    /// Отправить всё записанное
+    fn send_pending(&mut self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let buf = std::mem::take(&mut self.buf);
+        self.sent += buf.len() as u64;
+        if self.out.blocking_send(Ok(buf)).is_err() {
+            self.closed = true;
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "export client disconnected"));
+        }
+        Ok(())
+    }
+}
+
+impl Write for ChunkSender {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        // После отключения клиента ZipWriter ещё дописывает архив при drop - молча отбрасываем
+        if self.closed {
+            self.pos += data.len() as u64;
+            return Ok(data.len());
+        }
+        // seek держит позицию в пределах буфера
+        let offset = (self.pos - self.sent) as usize;
+        let overlap = data.len().min(self.buf.len() - offset);
+        self.buf[offset..offset + overlap].copy_from_slice(&data[..overlap]);
+        self.buf.extend_from_slice(&data[overlap..]);
+        self.pos += data.len() as u64;
+        Ok(data.len())
+    }
+
+    /// Отправка - по возврату в конец (см. seek) и из send_pending
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for ChunkSender {
+    fn seek(&mut self, to: SeekFrom) -> io::Result<u64> {
+        let end = self.end();
+        let target = match to {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => end.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.pos.checked_add_signed(delta),
+        };
+        if self.closed {
+            self.pos = target.unwrap_or(self.pos);
+            return Ok(self.pos);
+        }
+        let target = target
+            .filter(|target| (self.sent..=end).contains(target))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "seek outside the unsent part of the export"))?;
+
+        // Возврат в конец после правки заголовка: записанное до конца уже не изменится
+        let back_to_end = target == end && self.pos < end;
+        self.pos = target;
+        if back_to_end {
+            self.send_pending()?;
+        }
+        Ok(target)
+    }
+}
+
+fn write_csv_record<I, T>(buf: &mut Vec<u8>, record: I) -> Result<(), ApiError>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let mut writer = csv::WriterBuilder::new().buffer_capacity(1024).from_writer(buf);
+    writer
+        .write_record(record)
+        .and_then(|_| writer.flush().map_err(csv::Error::from))
+        .map_err(|e| ApiError::InternalError(format!("CSV export failed: {}", e)))
+}
+
+/// Excel не принимает строки длиннее 32767 UTF-16 единиц (символ вне BMP, например 🚀, - две).
+/// Длинная строка обрезается по границе символа с меткой XLSX_TRUNCATED_MARKER в конце;
+/// None - строка помещается как есть
+fn truncate_cell(value: &str) -> Option<String> {
+    // UTF-16 единиц не больше, чем байт UTF-8
+    if value.len() <= XLSX_MAX_STRING_UTF16 || value.encode_utf16().count() <= XLSX_MAX_STRING_UTF16 {
+        return None;
+    }
+    let budget = XLSX_MAX_STRING_UTF16 - XLSX_TRUNCATED_MARKER.encode_utf16().count();
+    let mut units = 0;
+    let mut end = 0;
+    for (i, c) in value.char_indices() {
+        units += c.len_utf16();
+        if units > budget {
+            break;
+        }
+        end = i + c.len_utf8();
+    }
+    Some(format!("{}{}", &value[..end], XLSX_TRUNCATED_MARKER))
+}
+
+fn export_io_error(e: io::Error) -> ApiError {
+    ApiError::InternalError(format!("Export write failed: {}", e))
+}
+
+fn xlsx_error(e: XlsxError) -> ApiError {
+    ApiError::InternalError(format!("XLSX export failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use std::io::Cursor;
+
+    fn row(id: i64, title: &str) -> OsdrExportRow {
+        OsdrExportRow {
+            id,
+            dataset_id: Some(format!("OSD-{}", id)),
+            title: Some(title.to_string()),
+            status: Some("public".to_string()),
+            mission: None,
+            organisms: vec!["Mus musculus".to_string()],
+            assay_types: Vec::new(),
+            factors: vec!["Spaceflight".to_string(), "Radiation".to_string()],
+            updated_at: Some(Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap()),
+            inserted_at: Utc.with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap(),
+            description: None,
+        }
+    }
+
+    fn encode_rows(format: OsdrExportFormat, rows: &[OsdrExportRow]) -> Vec<u8> {
+        let mut writer = OsdrExportWriter::new(format).unwrap();
+        let mut out = Vec::new();
+        for row in rows {
+            writer.write(row).unwrap();
+            out.extend(writer.take_chunk());
+        }
+        let mut rest = Cursor::new(Vec::new());
+        writer.finish(&mut rest).unwrap();
+        out.extend(rest.into_inner());
+        out
+    }
+
+    #[test]
+    fn csv_header_and_quoting() {
+        let csv = encode_rows(OsdrExportFormat::Csv, &[row(1, "Mice, \"RR-1\"\nday 2")]);
+        let csv = String::from_utf8(csv).unwrap();
+        let (header, body) = csv.split_once('\n').unwrap();
+
+        assert_eq!(header, OSDR_EXPORT_COLUMNS.join(","));
+        assert!(body.starts_with("1,OSD-1,\"Mice, \"\"RR-1\"\"\nday 2\",public,,Mus musculus,,"));
+        assert!(body.contains(",Spaceflight; Radiation,2024-03-01T12:00:00+00:00,"));
+        assert!(body.ends_with('\n'));
+    }
+
+    #[test]
+    fn jsonl_one_line_per_row() {
+        let jsonl = encode_rows(OsdrExportFormat::Jsonl, &[row(1, "first\nline"), row(2, "second")]);
+        let jsonl = String::from_utf8(jsonl).unwrap();
+        let lines: Vec<&str> = jsonl.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["dataset_id"], "OSD-1");
+        assert_eq!(first["title"], "first\nline");
+        let second: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(second["id"], 2);
+    }
+
+    #[test]
+    fn truncate_cell_counts_utf16_units() {
+        assert_eq!(truncate_cell("short"), None);
+        let exact = "ж".repeat(XLSX_MAX_STRING_UTF16);
+        assert_eq!(truncate_cell(&exact), None);
+
+        // 32766 символов, но 32768 UTF-16 единиц: 🚀 считается за две
+        let astral = format!("{}🚀", "ж".repeat(XLSX_MAX_STRING_UTF16 - 1));
+        let cut = truncate_cell(&astral).expect("over the UTF-16 limit");
+        assert_eq!(cut.encode_utf16().count(), XLSX_MAX_STRING_UTF16);
+        assert!(cut.ends_with(XLSX_TRUNCATED_MARKER));
+
+        // 🚀 не режется пополам на границе бюджета
+        let budget = XLSX_MAX_STRING_UTF16 - XLSX_TRUNCATED_MARKER.encode_utf16().count();
+        let split = format!("{}🚀{}", "a".repeat(budget - 1), "b".repeat(100));
+        let cut = truncate_cell(&split).expect("over the UTF-16 limit");
+        assert_eq!(cut, format!("{}{}", "a".repeat(budget - 1), XLSX_TRUNCATED_MARKER));
+    }
+
+    #[test]
+    fn xlsx_finish_writes_zip() {
+        let xlsx = encode_rows(OsdrExportFormat::Xlsx, &[row(1, "Rodent Research")]);
+        assert!(xlsx.len() > 1000);
+        assert!(xlsx.starts_with(b"PK\x03\x04"));
+    }
+
+    #[test]
+    fn xlsx_streamed_through_channel_is_complete_zip() {
+        let (rows_tx, rows_rx) = mpsc::channel(4);
+        let (out_tx, mut out_rx) = mpsc::channel(1024);
+        rows_tx.try_send(Ok(row(1, "Rodent Research"))).unwrap();
+        rows_tx.try_send(Ok(row(2, "Plant Biology"))).unwrap();
+        drop(rows_tx);
+
+        assert_eq!(encode(OsdrExportFormat::Xlsx, rows_rx, out_tx).unwrap(), 2);
+        let mut xlsx = Vec::new();
+        let mut chunks = 0;
+        while let Ok(chunk) = out_rx.try_recv() {
+            xlsx.extend(chunk.unwrap());
+            chunks += 1;
+        }
+
+        // Записи архива уходят по мере готовности, а не одним куском
+        assert!(chunks > 1);
+        assert!(xlsx.starts_with(b"PK\x03\x04"));
+        // Конец центрального каталога: каталог заканчивается ровно перед ним
+        let eocd = xlsx.len() - 22;
+        assert_eq!(&xlsx[eocd..eocd + 4], b"PK\x05\x06");
+        let dir_size = u32::from_le_bytes(xlsx[eocd + 12..eocd + 16].try_into().unwrap()) as usize;
+        let dir_offset = u32::from_le_bytes(xlsx[eocd + 16..eocd + 20].try_into().unwrap()) as usize;
+        assert_eq!(dir_offset + dir_size, eocd);
+        // CRC первой записи дописан в её заголовок после данных
+        assert_ne!(&xlsx[14..18], &[0, 0, 0, 0]);
+    }
+}
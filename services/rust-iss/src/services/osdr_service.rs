@@ -2,129 +2,482 @@ use crate::{
     clients::NasaClient,
     domain::{osdr::*, ApiError},
     repo::OsdrRepo,
+    services::osdr_export::{self, OSDR_EXPORT_ROW_BUFFER, OSDR_EXPORT_XLSX_MAX_ROWS},
 };
 use serde_json::Value;
-use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
-use uuid::Uuid;
+use chrono::{DateTime, Duration, Utc};
+use std::{collections::HashMap, io, time::Instant};
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+
+/// Максимальный размер страницы для /osdr/list
+pub const OSDR_LIST_MAX_PER_PAGE: i64 = 500;
+/// Максимальный размер страницы для /osdr/search
+pub const OSDR_SEARCH_MAX_PER_PAGE: i64 = 100;
+/// Максимальная длина поискового запроса
+pub const OSDR_SEARCH_MAX_QUERY_LEN: usize = 500;
+/// Сколько значений каждого фасета возвращать
+pub const OSDR_FACET_LIMIT: i64 = 20;
+/// Записей в одном многострочном upsert
+pub const OSDR_SYNC_BATCH_SIZE: usize = 200;
+/// Период /osdr/changes, если since не указан
+pub const OSDR_CHANGES_DEFAULT_DAYS: i64 = 7;
+/// Максимум изменений в ответе /osdr/changes
+pub const OSDR_CHANGES_MAX_LIMIT: i64 = 1000;
+/// Максимальный размер страницы /osdr/quarantine
+pub const OSDR_QUARANTINE_MAX_PER_PAGE: i64 = 200;
+/// Месяцев в распределении /osdr/stats по дате обновления
+pub const OSDR_STATS_MONTHS: i64 = 24;
 
 /// Сервис для работы с данными OSDR
 pub struct OsdrService {
     repo: OsdrRepo,
     client: NasaClient,
     osdr_url: String,
+    // Шаблон URL списка файлов датасета (OSDR_FILES_URL)
+    files_url: String,
+    // Манифестов файлов за одну синхронизацию (OSDR_FILES_PER_SYNC)
+    files_per_sync: i64,
+    // Страниц каталога за одну синхронизацию (OSDR_MAX_PAGES)
+    max_pages: u32,
+    // Размер страницы /osdr/list по умолчанию (OSDR_LIST_LIMIT)
+    default_per_page: i64,
 }
 
 impl OsdrService {
-    pub fn new(repo: OsdrRepo, client: NasaClient, osdr_url: String) -> Self {
+    pub fn new(
+        repo: OsdrRepo,
+        client: NasaClient,
+        osdr_url: String,
+        files_url: String,
+        files_per_sync: i64,
+        max_pages: u32,
+        default_per_page: i64,
+    ) -> Self {
         Self {
             repo,
             client,
             osdr_url,
+            files_url,
+            files_per_sync,
+            max_pages,
+            default_per_page,
         }
     }
 
-    /// Синхронизация данных OSDR
-    pub async fn sync(&self) -> Result<usize, ApiError> {
-        let json = self.client.fetch_osdr(&self.osdr_url).await?;
-        
-        // NASA OSDR API возвращает объект с ключами dataset_id
-        let items = if let Some(obj) = json.as_object() {
-            // Конвертируем объект { "OSD-1": {...}, "OSD-100": {...} } 
-            // в массив объектов с добавлением поля dataset_id
-            obj.iter().map(|(key, value)| {
-                let mut item = value.clone();
-                // Добавляем dataset_id как ключ
-                if let Some(o) = item.as_object_mut() {
-                    o.insert("dataset_id".to_string(), serde_json::Value::String(key.clone()));
-                }
-                item
-            }).collect::<Vec<_>>()
-        } else if let Some(a) = json.as_array() {
-            a.clone()
-        } else if let Some(v) = json.get("items").and_then(|x| x.as_array()) {
-            v.clone()
-        } else if let Some(v) = json.get("results").and_then(|x| x.as_array()) {
-            v.clone()
-        } else {
-            vec![json.clone()]
+    /// Синхронизация данных OSDR: каталог обходится постранично, не больше max_pages страниц за раз.
+    /// Позиция обхода сохраняется после каждой страницы - после ошибки или лимита следующая
+    /// синхронизация продолжит с недогруженной страницы. Документы, не прошедшие нормализацию,
+    /// попадают в карантин (osdr_quarantine). Записи с прежним хешем содержимого
+    /// пропускаются, остальные пишутся пачками по OSDR_SYNC_BATCH_SIZE. Ошибка пачки не прерывает
+    /// синхронизацию, её записи попадают в failed. Затем загружаются манифесты файлов новых
+    /// и изменившихся датасетов.
+    pub async fn sync(&self) -> Result<OsdrSyncReport, ApiError> {
+        let started_at = Utc::now();
+        let started = Instant::now();
+        let start = self
+            .repo
+            .load_cursor(&self.osdr_url)
+            .await?
+            .unwrap_or_else(|| OsdrCursor { url: self.osdr_url.clone(), page: 1 });
+
+        let mut report = OsdrSyncReport {
+            started_at,
+            fetched: 0,
+            inserted: 0,
+            updated: 0,
+            unchanged: 0,
+            failed: 0,
+            quarantined: 0,
+            batches: 0,
+            start_page: start.page,
+            pages: 0,
+            complete: false,
+            next_page: None,
+            page_error: None,
+            manifests: 0,
+            files: 0,
+            manifest_failed: 0,
+            duration_ms: 0,
         };
 
-        let mut written = 0usize;
+        let mut walker = self.client.walk_osdr(start, self.max_pages);
+        loop {
+            match walker.next_page().await {
+                Ok(Some((_, page))) => {
+                    report.pages += 1;
+                    self.sync_items(page.items, &mut report).await?;
+                    self.repo.save_cursor(&self.osdr_url, walker.resume_cursor()).await?;
+                }
+                Ok(None) => break,
+                Err(e) if report.pages == 0 => {
+                    // Сохранённая страница пропала у NASA - следующая синхронизация начнёт сначала
+                    if report.start_page > 1 && e.code().starts_with("UPSTREAM_4") {
+                        self.repo.save_cursor(&self.osdr_url, None).await?;
+                    }
+                    return Err(e);
+                }
+                Err(e) => {
+                    let page = walker.resume_cursor().map_or(0, |c| c.page);
+                    warn!("OSDR catalogue page {} failed: {:?}", page, e);
+                    report.page_error = Some(OsdrPageError {
+                        page,
+                        code: e.code().to_string(),
+                        message: e.message(),
+                    });
+                    break;
+                }
+            }
+        }
+        report.next_page = walker.resume_cursor().map(|c| c.page);
+        report.complete = report.next_page.is_none();
+
+        for dataset_id in self.repo.datasets_pending_files(self.files_per_sync).await? {
+            match self.sync_files(&dataset_id).await {
+                Ok(count) => {
+                    report.manifests += 1;
+                    report.files += count;
+                }
+                Err(e) => {
+                    warn!("OSDR file manifest for {} failed: {:?}", dataset_id, e);
+                    report.manifest_failed += 1;
+                }
+            }
+        }
+        report.duration_ms = started.elapsed().as_millis() as i64;
+
+        Ok(report)
+    }
+
+    /// Сохранить записи одной страницы каталога
+    async fn sync_items(&self, items: Vec<Value>, report: &mut OsdrSyncReport) -> Result<(), ApiError> {
+        // Повторы dataset_id на странице: остаётся последняя запись (одна строка на ключ в пачке);
+        // повторы отклонённых документов - по хешу
+        let mut records: Vec<OsdrRecord> = Vec::with_capacity(items.len());
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        let mut rejected: HashMap<String, OsdrRejected> = HashMap::new();
         for item in items {
-            let orig_dataset_id = extract_string(&item, &["dataset_id", "id", "uuid", "studyId", "accession", "osdr_id"]);
-            // Если dataset_id отсутствует — сгенерируем детерминированный fallback
-            // из JSON-представления записи (UUID v5 на основе содержимого).
-            // Это позволяет избежать множества NULL-записей и дубликатов при последующих sync.
-            let dataset_id = match orig_dataset_id {
-                Some(s) => Some(s),
-                None => {
-                    // Используем стабильную генерацию: UUIDv5(namespace OID, raw_json)
-                    let raw_text = item.to_string();
-                    let generated = format!("gen-{}", Uuid::new_v5(&Uuid::NAMESPACE_OID, raw_text.as_bytes()));
-                    Some(generated)
+            let record = match OsdrRecord::normalize(item) {
+                Ok(record) => record,
+                Err(r) => {
+                    rejected.insert(r.content_hash.clone(), r);
+                    continue;
                 }
             };
-            let title = extract_string(&item, &["title", "name", "label"]);
-            let status = extract_string(&item, &["status", "state", "lifecycle"]);
-            let updated_at = extract_timestamp(&item, &["updated", "updated_at", "modified", "lastUpdated", "timestamp"]);
+            match positions.get(&record.dataset_id) {
+                Some(&index) => records[index] = record,
+                None => {
+                    positions.insert(record.dataset_id.clone(), records.len());
+                    records.push(record);
+                }
+            }
+        }
 
-            self.repo
-                .upsert(dataset_id, title, status, updated_at, item)
-                .await?;
+        if !rejected.is_empty() {
+            let rejected: Vec<OsdrRejected> = rejected.into_values().collect();
+            for r in &rejected {
+                warn!(
+                    "OSDR record {} quarantined: {}",
+                    r.dataset_id.as_deref().unwrap_or("without dataset_id"),
+                    r.errors.iter().map(|e| format!("{}: {}", e.field, e.message)).collect::<Vec<_>>().join("; ")
+                );
+            }
+            self.repo.quarantine(&rejected).await?;
+            report.quarantined += rejected.len();
+        }
+
+        let ids: Vec<String> = records.iter().map(|r| r.dataset_id.clone()).collect();
+        let known = self.repo.content_hashes(&ids).await?;
+        let fetched = records.len();
+        let pending: Vec<OsdrRecord> = records
+            .into_iter()
+            .filter(|r| known.get(&r.dataset_id).and_then(|h| h.as_deref()) != Some(r.content_hash.as_str()))
+            .collect();
+        report.fetched += fetched;
+        report.unchanged += fetched - pending.len();
 
-            written += 1;
+        for batch in pending.chunks(OSDR_SYNC_BATCH_SIZE) {
+            report.batches += 1;
+            match self.repo.upsert_batch(batch).await {
+                Ok(result) => {
+                    report.inserted += result.inserted;
+                    report.updated += result.updated;
+                    report.unchanged += result.unchanged;
+                    let ids: Vec<&str> = batch.iter().map(|r| r.dataset_id.as_str()).collect();
+                    self.repo.release_quarantined(&ids).await?;
+                }
+                Err(e) => {
+                    error!("OSDR batch of {} items failed: {:?}", batch.len(), e);
+                    report.failed += batch.len();
+                }
+            }
         }
 
-        Ok(written)
+        Ok(())
     }
 
-    /// Получить список элементов с пагинацией
-    pub async fn list(&self, limit: i64, offset: i64) -> Result<OsdrListResponse, ApiError> {
-        let items = self.repo.list(limit, offset).await?;
-        let total = items.len();
+    /// Получить страницу списка с фильтром и сортировкой (`page` с единицы)
+    pub async fn list(
+        &self,
+        filter: OsdrFilter,
+        sort: OsdrSort,
+        order: SortOrder,
+        page: i64,
+        per_page: Option<i64>,
+    ) -> Result<OsdrListResponse, ApiError> {
+        let per_page = per_page.unwrap_or(self.default_per_page);
+        if !(1..=OSDR_LIST_MAX_PER_PAGE).contains(&per_page) {
+            return Err(ApiError::ValidationError(format!(
+                "'per_page' must be between 1 and {}", OSDR_LIST_MAX_PER_PAGE
+            )));
+        }
+        if page < 1 {
+            return Err(ApiError::ValidationError("'page' must be at least 1".to_string()));
+        }
+        let offset = (page - 1)
+            .checked_mul(per_page)
+            .ok_or_else(|| ApiError::ValidationError("'page' is too large".to_string()))?;
+
+        let items = self.repo.list(&filter, sort, order, per_page, offset).await?;
+        let total = self.repo.count_filtered(&filter).await?;
 
-        Ok(OsdrListResponse { items, total })
+        Ok(OsdrListResponse {
+            items,
+            total,
+            page,
+            per_page,
+            total_pages: (total + per_page - 1) / per_page,
+        })
     }
 
-    /// Получить количество записей
-    pub async fn count(&self) -> Result<i64, ApiError> {
-        self.repo.count().await
+    /// Полнотекстовый поиск с подсветкой и фасетами (синтаксис websearch: "фраза", OR, -исключение)
+    pub async fn search(
+        &self,
+        query: &str,
+        facets: OsdrFacetFilter,
+        page: i64,
+        per_page: i64,
+    ) -> Result<OsdrSearchResponse, ApiError> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(ApiError::ValidationError("'q' must not be empty".to_string()));
+        }
+        if query.chars().count() > OSDR_SEARCH_MAX_QUERY_LEN {
+            return Err(ApiError::ValidationError(format!(
+                "'q' cannot exceed {} characters", OSDR_SEARCH_MAX_QUERY_LEN
+            )));
+        }
+        if !(1..=OSDR_SEARCH_MAX_PER_PAGE).contains(&per_page) {
+            return Err(ApiError::ValidationError(format!(
+                "'per_page' must be between 1 and {}", OSDR_SEARCH_MAX_PER_PAGE
+            )));
+        }
+        if page < 1 {
+            return Err(ApiError::ValidationError("'page' must be at least 1".to_string()));
+        }
+        let offset = (page - 1)
+            .checked_mul(per_page)
+            .ok_or_else(|| ApiError::ValidationError("'page' is too large".to_string()))?;
+
+        let items = self.repo.search(query, &facets, per_page, offset).await?;
+        let total = self.repo.search_count(query, &facets).await?;
+        let facet_counts = self.repo.search_facets(query, &facets, OSDR_FACET_LIMIT).await?;
+
+        Ok(OsdrSearchResponse {
+            query: query.to_string(),
+            items,
+            total,
+            page,
+            per_page,
+            total_pages: (total + per_page - 1) / per_page,
+            facets: facet_counts,
+        })
     }
-}
 
-/// Извлечение строки из JSON по нескольким возможным ключам
-fn extract_string(value: &Value, keys: &[&str]) -> Option<String> {
-    for k in keys {
-        if let Some(x) = value.get(*k) {
-            if let Some(s) = x.as_str() {
-                if !s.is_empty() {
-                    return Some(s.to_string());
-                }
-            } else if x.is_number() {
-                return Some(x.to_string());
-            }
+    /// Датасет по dataset_id с нормализованными метаданными
+    pub async fn get_dataset(&self, dataset_id: &str) -> Result<OsdrDataset, ApiError> {
+        let item = self
+            .repo
+            .find_by_dataset_id(dataset_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("OSDR dataset '{}' not found", dataset_id)))?;
+
+        Ok(OsdrDataset::from_item(item))
+    }
+
+    /// Загрузить манифест файлов датасета; датасет без списка файлов (404) получает пустой манифест,
+    /// чтобы не запрашивать его при каждой синхронизации
+    async fn sync_files(&self, dataset_id: &str) -> Result<usize, ApiError> {
+        let url = self
+            .files_url
+            .replace("{dataset_id}", dataset_id)
+            .replace("{id}", dataset_id.trim_start_matches("OSD-"));
+
+        let files = match self.client.fetch_osdr_files(&url).await {
+            Ok(json) => parse_file_manifest(&json, url_origin(&url)),
+            Err(ApiError::UpstreamError { code, .. }) if code == "UPSTREAM_404" => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        self.repo.replace_files(dataset_id, &files).await?;
+
+        Ok(files.len())
+    }
+
+    /// Файлы датасета; манифест, который ещё не загружался, загружается сразу
+    pub async fn files(&self, dataset_id: &str) -> Result<OsdrFileList, ApiError> {
+        let mut synced_at = self
+            .repo
+            .files_synced_at(dataset_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("OSDR dataset '{}' not found", dataset_id)))?;
+        if synced_at.is_none() {
+            self.sync_files(dataset_id).await?;
+            synced_at = self.repo.files_synced_at(dataset_id).await?.flatten();
         }
+
+        let files = self.repo.files(dataset_id).await?;
+        Ok(OsdrFileList {
+            dataset_id: dataset_id.to_string(),
+            synced_at,
+            total_size_bytes: files.iter().filter_map(|f| f.size_bytes).sum(),
+            files,
+        })
     }
-    None
-}
 
-/// Извлечение timestamp из JSON
-fn extract_timestamp(value: &Value, keys: &[&str]) -> Option<DateTime<Utc>> {
-    for k in keys {
-        if let Some(x) = value.get(*k) {
-            if let Some(s) = x.as_str() {
-                // Попытка парсинга ISO 8601
-                if let Ok(dt) = s.parse::<DateTime<Utc>>() {
-                    return Some(dt);
-                }
-                // Попытка парсинга других форматов
-                if let Ok(ndt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
-                    return Some(Utc.from_utc_datetime(&ndt));
-                }
-            } else if let Some(n) = x.as_i64() {
-                return Some(Utc.timestamp_opt(n, 0).single().unwrap_or_else(Utc::now));
-            }
+    /// Открыть файл датасета у NASA для потоковой передачи клиенту
+    pub async fn download(
+        &self,
+        dataset_id: &str,
+        file_id: i64,
+        range: Option<&str>,
+    ) -> Result<(OsdrFile, reqwest::Response), ApiError> {
+        let file = self
+            .repo
+            .file(dataset_id, file_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("File {} of OSDR dataset '{}' not found", file_id, dataset_id)))?;
+        let response = self.client.download(&file.url, range).await?;
+
+        Ok((file, response))
+    }
+
+    /// История версий датасета
+    pub async fn history(&self, dataset_id: &str) -> Result<OsdrHistory, ApiError> {
+        let item = self
+            .repo
+            .find_by_dataset_id(dataset_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("OSDR dataset '{}' not found", dataset_id)))?;
+        let versions = self.repo.versions(item.id).await?;
+
+        Ok(OsdrHistory {
+            dataset_id: dataset_id.to_string(),
+            item_id: item.id,
+            versions,
+        })
+    }
+
+    /// Новые и изменённые датасеты после `since` (по умолчанию - за OSDR_CHANGES_DEFAULT_DAYS)
+    pub async fn changes(
+        &self,
+        since: Option<DateTime<Utc>>,
+        change: Option<OsdrChangeKind>,
+        limit: i64,
+    ) -> Result<OsdrChanges, ApiError> {
+        if !(1..=OSDR_CHANGES_MAX_LIMIT).contains(&limit) {
+            return Err(ApiError::ValidationError(format!(
+                "'limit' must be between 1 and {}", OSDR_CHANGES_MAX_LIMIT
+            )));
+        }
+        let since = since.unwrap_or_else(|| Utc::now() - Duration::days(OSDR_CHANGES_DEFAULT_DAYS));
+
+        let items = self.repo.changes_since(since, change, limit).await?;
+        let total = self.repo.count_changes_since(since, change).await?;
+
+        Ok(OsdrChanges { since, total, items })
+    }
+
+    /// Документы в карантине, при `dataset_id` - только этого датасета (`page` с единицы)
+    pub async fn quarantine(
+        &self,
+        dataset_id: Option<&str>,
+        page: i64,
+        per_page: i64,
+    ) -> Result<OsdrQuarantinePage, ApiError> {
+        if !(1..=OSDR_QUARANTINE_MAX_PER_PAGE).contains(&per_page) {
+            return Err(ApiError::ValidationError(format!(
+                "'per_page' must be between 1 and {}", OSDR_QUARANTINE_MAX_PER_PAGE
+            )));
+        }
+        if page < 1 {
+            return Err(ApiError::ValidationError("'page' must be at least 1".to_string()));
+        }
+        let offset = (page - 1)
+            .checked_mul(per_page)
+            .ok_or_else(|| ApiError::ValidationError("'page' is too large".to_string()))?;
+
+        let items = self.repo.quarantined(dataset_id, per_page, offset).await?;
+        let total = self.repo.count_quarantined(dataset_id).await?;
+
+        Ok(OsdrQuarantinePage {
+            items,
+            total,
+            page,
+            per_page,
+            total_pages: (total + per_page - 1) / per_page,
+        })
+    }
+
+    /// Сводная статистика каталога для дашборда
+    pub async fn stats(&self) -> Result<OsdrStats, ApiError> {
+        self.repo.stats(OSDR_FACET_LIMIT, OSDR_STATS_MONTHS).await
+    }
+
+    /// Проверка выгрузки до начала ответа: на лист XLSX помещается не больше
+    /// OSDR_EXPORT_XLSX_MAX_ROWS строк
+    pub async fn check_export(&self, filter: &OsdrFilter, format: OsdrExportFormat) -> Result<(), ApiError> {
+        if format != OsdrExportFormat::Xlsx {
+            return Ok(());
+        }
+        let rows = self.repo.count_filtered(filter).await?;
+        if rows > OSDR_EXPORT_XLSX_MAX_ROWS as i64 {
+            return Err(ApiError::ValidationError(format!(
+                "XLSX export is limited to {} rows, {} match the filter; use csv or jsonl",
+                OSDR_EXPORT_XLSX_MAX_ROWS, rows
+            )));
         }
+        Ok(())
+    }
+
+    /// Выгрузка записей под фильтром списка частями в `out`. Строки читаются из БД построчно;
+    /// ошибка после начала передачи обрывает поток (клиент получит неполный ответ, а не битый файл).
+    /// Кодирование блокирующее и идёт в spawn_blocking.
+    pub async fn export(
+        &self,
+        filter: OsdrFilter,
+        sort: OsdrSort,
+        order: SortOrder,
+        format: OsdrExportFormat,
+        out: mpsc::Sender<Result<Vec<u8>, io::Error>>,
+    ) {
+        let (rows_tx, rows_rx) = mpsc::channel(OSDR_EXPORT_ROW_BUFFER);
+        let fetch = self.repo.export(&filter, sort, order, rows_tx);
+        let sink = out.clone();
+        let encode = async {
+            tokio::task::spawn_blocking(move || osdr_export::encode(format, rows_rx, sink))
+                .await
+                .map_err(|e| ApiError::InternalError(format!("Export task failed: {}", e)))?
+        };
+
+        let ((), encoded) = tokio::join!(fetch, encode);
+        if let Err(e) = encoded {
+            error!("OSDR {} export failed: {:?}", format.extension(), e);
+            let _ = out.send(Err(io::Error::other(e.message()))).await;
+        }
+    }
+
+    /// Получить количество записей
+    pub async fn count(&self) -> Result<i64, ApiError> {
+        self.repo.count().await
     }
-    None
 }
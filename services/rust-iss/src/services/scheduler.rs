@@ -1,20 +1,31 @@
 use crate::{
     config::AppConfig,
-    services::{IssService, OsdrService, SpaceService},
+    domain::sync_run::*,
+    services::{IssService, OsdrService, SpaceService, SyncRunService},
 };
-use std::time::Duration;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use std::sync::Arc;
 use tracing::{error, info};
 
+/// Размер пачки для бэкфилла iss_fetch_log
+const ISS_BACKFILL_BATCH: i64 = 1000;
+/// Как часто планировщик сверяется с реестром отслеживаемых объектов
+const OBJECTS_TICK: Duration = Duration::from_secs(5);
+/// Как часто чистить журнал sync_runs (первый раз - при старте)
+const SYNC_RUNS_PURGE_EVERY: Duration = Duration::from_secs(86400);
+
 /// Планировщик фоновых задач
 pub struct Scheduler {
     config: AppConfig,
     iss_service: Arc<IssService>,
     osdr_service: Arc<OsdrService>,
     space_service: Arc<SpaceService>,
+    sync_runs: Arc<SyncRunService>,
     // Mutex для предотвращения наложения задач
     iss_lock: Arc<Mutex<()>>,
+    tle_lock: Arc<Mutex<()>>,
     osdr_lock: Arc<Mutex<()>>,
     apod_lock: Arc<Mutex<()>>,
     neo_lock: Arc<Mutex<()>>,
@@ -28,13 +39,16 @@ impl Scheduler {
         iss_service: Arc<IssService>,
         osdr_service: Arc<OsdrService>,
         space_service: Arc<SpaceService>,
+        sync_runs: Arc<SyncRunService>,
     ) -> Self {
         Self {
             config,
             iss_service,
             osdr_service,
             space_service,
+            sync_runs,
             iss_lock: Arc::new(Mutex::new(())),
+            tle_lock: Arc::new(Mutex::new(())),
             osdr_lock: Arc::new(Mutex::new(())),
             apod_lock: Arc::new(Mutex::new(())),
             neo_lock: Arc::new(Mutex::new(())),
@@ -45,7 +59,10 @@ impl Scheduler {
 
     /// Запустить все фоновые задачи
     pub fn start_all(&self) {
+        self.start_iss_backfill();
+        self.start_sync_runs_purge();
         self.start_iss_fetcher();
+        self.start_tle_fetcher();
         self.start_osdr_fetcher();
         self.start_apod_fetcher();
         self.start_neo_fetcher();
@@ -53,19 +70,143 @@ impl Scheduler {
         self.start_spacex_fetcher();
     }
 
+    /// Однократный бэкфилл типизированных колонок iss_fetch_log
+    fn start_iss_backfill(&self) {
+        let service = Arc::clone(&self.iss_service);
+        let runs = Arc::clone(&self.sync_runs);
+
+        tokio::spawn(async move {
+            let backfill = runs.track(
+                JOB_ISS_BACKFILL,
+                RunTrigger::Scheduler,
+                service.backfill_typed_columns(ISS_BACKFILL_BATCH),
+                |count| RunSummary::items(*count as i64),
+            );
+            match backfill.await {
+                Ok(0) => {}
+                Ok(count) => info!("ISS backfill: {} rows updated", count),
+                Err(e) => error!("ISS backfill error: {:?}", e),
+            }
+        });
+    }
+
+    /// Удаление записей sync_runs старше срока хранения
+    fn start_sync_runs_purge(&self) {
+        let runs = Arc::clone(&self.sync_runs);
+        let retention_days = self.config.sync_runs_retention_days;
+
+        tokio::spawn(async move {
+            info!("Sync runs purge started, retention: {} days", retention_days);
+            loop {
+                match runs.purge(retention_days).await {
+                    Ok(0) => {}
+                    Ok(count) => info!("Sync runs purge: {} runs deleted", count),
+                    Err(e) => error!("Sync runs purge error: {:?}", e),
+                }
+                tokio::time::sleep(SYNC_RUNS_PURGE_EVERY).await;
+            }
+        });
+    }
+
+    /// Опрос объектов из tracked_objects, каждого со своим интервалом
     fn start_iss_fetcher(&self) {
         let service = Arc::clone(&self.iss_service);
+        let runs = Arc::clone(&self.sync_runs);
         let lock = Arc::clone(&self.iss_lock);
-        let interval = self.config.fetch_every_iss;
+        let default_interval = self.config.fetch_every_iss;
 
         tokio::spawn(async move {
-            info!("ISS fetcher started with interval: {}s", interval);
+            info!("Tracked objects fetcher started, default interval: {}s", default_interval);
+            let mut last_run: HashMap<i32, Instant> = HashMap::new();
             loop {
                 // Защита от наложения
                 let _guard = lock.lock().await;
+
+                match service.list_objects(true).await {
+                    Ok(objects) => {
+                        let due: Vec<_> = objects
+                            .into_iter()
+                            .filter(|object| {
+                                let interval = object
+                                    .poll_every_seconds
+                                    .map(|s| s.max(1) as u64)
+                                    .unwrap_or(default_interval);
+                                last_run
+                                    .get(&object.norad_id)
+                                    .is_none_or(|at| at.elapsed() >= Duration::from_secs(interval))
+                            })
+                            .collect();
+
+                        // Один запуск в журнале на такт, в котором пришла пора опросить хотя бы один объект
+                        if !due.is_empty() {
+                            let fetch_due = async {
+                                let mut summary = RunSummary::default();
+                                let mut norad_ids = Vec::new();
+                                for object in &due {
+                                    last_run.insert(object.norad_id, Instant::now());
+                                    norad_ids.push(object.norad_id);
+                                    match service.fetch_and_store(object.norad_id).await {
+                                        Ok(_) => summary.items += 1,
+                                        Err(e) => {
+                                            error!("Fetch error for {} (NORAD {}): {:?}", object.name, object.norad_id, e);
+                                            summary.record_failure(&e);
+                                        }
+                                    }
+                                }
+                                Ok(summary.with_details(serde_json::json!({ "norad_ids": norad_ids })))
+                            };
+                            let _ = runs
+                                .track(JOB_ISS_FETCH, RunTrigger::Scheduler, fetch_due, RunSummary::clone)
+                                .await;
+                        }
+                    }
+                    Err(e) => {
+                        // Без реестра опрос стоит целиком - такой такт тоже попадает в журнал
+                        let failed = runs.track(JOB_ISS_FETCH, RunTrigger::Scheduler, async { Err(e) }, RunSummary::clone);
+                        if let Err(e) = failed.await {
+                            error!("Tracked objects list error: {:?}", e);
+                        }
+                    }
+                }
+
+                drop(_guard);
+                tokio::time::sleep(OBJECTS_TICK).await;
+            }
+        });
+    }
+
+    fn start_tle_fetcher(&self) {
+        let service = Arc::clone(&self.iss_service);
+        let runs = Arc::clone(&self.sync_runs);
+        let lock = Arc::clone(&self.tle_lock);
+        let interval = self.config.fetch_every_tle;
+
+        tokio::spawn(async move {
+            info!("TLE fetcher started with interval: {}s", interval);
+            loop {
+                let _guard = lock.lock().await;
                 
-                if let Err(e) = service.fetch_and_store().await {
-                    error!("ISS fetch error: {:?}", e);
+                let refresh_all = async {
+                    let mut summary = RunSummary::default();
+                    for object in service.list_objects(true).await? {
+                        match service.refresh_tle(object.norad_id).await {
+                            Ok(tle) => {
+                                info!("TLE for {} refreshed, epoch {}", object.name, tle.epoch);
+                                summary.items += 1;
+                            }
+                            Err(e) => {
+                                error!("TLE fetch error for NORAD {}: {:?}", object.norad_id, e);
+                                summary.record_failure(&e);
+                            }
+                        }
+                    }
+                    Ok(summary)
+                };
+                if let Err(e) = runs
+                    .track(JOB_TLE_REFRESH, RunTrigger::Scheduler, refresh_all, RunSummary::clone)
+                    .await
+                {
+                    error!("Tracked objects list error: {:?}", e);
                 }
                 
                 drop(_guard);
@@ -76,6 +217,7 @@ impl Scheduler {
 
     fn start_osdr_fetcher(&self) {
         let service = Arc::clone(&self.osdr_service);
+        let runs = Arc::clone(&self.sync_runs);
         let lock = Arc::clone(&self.osdr_lock);
         let interval = self.config.fetch_every_osdr;
 
@@ -84,8 +226,13 @@ impl Scheduler {
             loop {
                 let _guard = lock.lock().await;
                 
-                match service.sync().await {
-                    Ok(count) => info!("OSDR synced: {} items", count),
+                match runs.track(JOB_OSDR_SYNC, RunTrigger::Scheduler, service.sync(), RunSummary::from_osdr).await {
+                    Ok(report) => info!(
+                        "OSDR synced: {} pages from {}, {} fetched, {} inserted, {} updated, {} unchanged, {} failed in {}ms{}",
+                        report.pages, report.start_page, report.fetched, report.inserted, report.updated,
+                        report.unchanged, report.failed, report.duration_ms,
+                        report.next_page.map(|p| format!(", continues from page {}", p)).unwrap_or_default()
+                    ),
                     Err(e) => error!("OSDR sync error: {:?}", e),
                 }
                 
@@ -97,6 +244,7 @@ impl Scheduler {
 
     fn start_apod_fetcher(&self) {
         let service = Arc::clone(&self.space_service);
+        let runs = Arc::clone(&self.sync_runs);
         let lock = Arc::clone(&self.apod_lock);
         let interval = self.config.fetch_every_apod;
 
@@ -105,7 +253,8 @@ impl Scheduler {
             loop {
                 let _guard = lock.lock().await;
                 
-                if let Err(e) = service.refresh_apod().await {
+                let refresh = runs.track(JOB_APOD, RunTrigger::Scheduler, service.refresh_apod(), |_| RunSummary::items(1));
+                if let Err(e) = refresh.await {
                     error!("APOD fetch error: {:?}", e);
                 }
                 
@@ -117,6 +266,7 @@ impl Scheduler {
 
     fn start_neo_fetcher(&self) {
         let service = Arc::clone(&self.space_service);
+        let runs = Arc::clone(&self.sync_runs);
         let lock = Arc::clone(&self.neo_lock);
         let interval = self.config.fetch_every_neo;
 
@@ -125,7 +275,8 @@ impl Scheduler {
             loop {
                 let _guard = lock.lock().await;
                 
-                if let Err(e) = service.refresh_neo().await {
+                let refresh = runs.track(JOB_NEO, RunTrigger::Scheduler, service.refresh_neo(), |_| RunSummary::items(1));
+                if let Err(e) = refresh.await {
                     error!("NEO fetch error: {:?}", e);
                 }
                 
@@ -137,6 +288,7 @@ impl Scheduler {
 
     fn start_donki_fetcher(&self) {
         let service = Arc::clone(&self.space_service);
+        let runs = Arc::clone(&self.sync_runs);
         let lock = Arc::clone(&self.donki_lock);
         let interval = self.config.fetch_every_donki;
 
@@ -145,8 +297,23 @@ impl Scheduler {
             loop {
                 let _guard = lock.lock().await;
                 
-                let _ = service.refresh_donki_flares().await;
-                let _ = service.refresh_donki_cme().await;
+                // Вспышки и выбросы массы - один запуск DONKI, ошибка одного источника не останавливает другой
+                let refresh_both = async {
+                    let mut summary = RunSummary::default();
+                    for result in [service.refresh_donki_flares().await, service.refresh_donki_cme().await] {
+                        match result {
+                            Ok(()) => summary.items += 1,
+                            Err(e) => {
+                                error!("DONKI fetch error: {:?}", e);
+                                summary.record_failure(&e);
+                            }
+                        }
+                    }
+                    Ok(summary)
+                };
+                let _ = runs
+                    .track(JOB_DONKI, RunTrigger::Scheduler, refresh_both, RunSummary::clone)
+                    .await;
                 
                 drop(_guard);
                 tokio::time::sleep(Duration::from_secs(interval)).await;
@@ -156,6 +323,7 @@ impl Scheduler {
 
     fn start_spacex_fetcher(&self) {
         let service = Arc::clone(&self.space_service);
+        let runs = Arc::clone(&self.sync_runs);
         let lock = Arc::clone(&self.spacex_lock);
         let interval = self.config.fetch_every_spacex;
 
@@ -164,7 +332,8 @@ impl Scheduler {
             loop {
                 let _guard = lock.lock().await;
                 
-                if let Err(e) = service.refresh_spacex().await {
+                let refresh = runs.track(JOB_SPACEX, RunTrigger::Scheduler, service.refresh_spacex(), |_| RunSummary::items(1));
+                if let Err(e) = refresh.await {
                     error!("SpaceX fetch error: {:?}", e);
                 }
                 
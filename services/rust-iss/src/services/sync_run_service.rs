@@ -0,0 +1,97 @@
+use crate::{
+    domain::{sync_run::*, ApiError},
+    repo::SyncRunRepo,
+};
+use chrono::{Duration, Utc};
+use std::future::Future;
+use tracing::warn;
+
+/// Максимальный размер страницы /admin/jobs/runs
+pub const SYNC_RUNS_MAX_PER_PAGE: i64 = 500;
+
+/// Журнал запусков задач загрузки данных
+pub struct SyncRunService {
+    repo: SyncRunRepo,
+}
+
+impl SyncRunService {
+    pub fn new(repo: SyncRunRepo) -> Self {
+        Self { repo }
+    }
+
+    /// Выполнить задачу с записью в sync_runs. `summarize` даёт счётчики по результату задачи.
+    /// Ошибки записи журнала только логируются - задача и её результат от них не зависят.
+    pub async fn track<T, Fut, S>(
+        &self,
+        job: &str,
+        trigger: RunTrigger,
+        task: Fut,
+        summarize: S,
+    ) -> Result<T, ApiError>
+    where
+        Fut: Future<Output = Result<T, ApiError>>,
+        S: FnOnce(&T) -> RunSummary,
+    {
+        let run_id = match self.repo.start(job, trigger).await {
+            Ok(id) => Some(id),
+            Err(e) => {
+                warn!("Failed to record {} run start: {:?}", job, e);
+                None
+            }
+        };
+
+        let result = task.await;
+
+        if let Some(id) = run_id {
+            let finished = match &result {
+                Ok(value) => {
+                    let summary = summarize(value);
+                    let error = summary.error.as_ref().map(|(code, message)| (code.as_str(), message.as_str()));
+                    self.repo.finish(id, summary.outcome(), Some(&summary), error).await
+                }
+                Err(e) => {
+                    let message = e.message();
+                    self.repo
+                        .finish(id, RunOutcome::Failed, None, Some((e.code(), message.as_str())))
+                        .await
+                }
+            };
+            if let Err(e) = finished {
+                warn!("Failed to record {} run result: {:?}", job, e);
+            }
+        }
+
+        result
+    }
+
+    /// Удалить запуски старше `retention_days` суток; возвращает число удалённых
+    pub async fn purge(&self, retention_days: i64) -> Result<u64, ApiError> {
+        self.repo.purge(Utc::now() - Duration::days(retention_days)).await
+    }
+
+    /// Страница журнала (`page` с единицы)
+    pub async fn list(&self, filter: SyncRunFilter, page: i64, per_page: i64) -> Result<SyncRunPage, ApiError> {
+        if !(1..=SYNC_RUNS_MAX_PER_PAGE).contains(&per_page) {
+            return Err(ApiError::ValidationError(format!(
+                "'per_page' must be between 1 and {}", SYNC_RUNS_MAX_PER_PAGE
+            )));
+        }
+        if page < 1 {
+            return Err(ApiError::ValidationError("'page' must be at least 1".to_string()));
+        }
+        let offset = (page - 1)
+            .checked_mul(per_page)
+            .ok_or_else(|| ApiError::ValidationError("'page' is too large".to_string()))?;
+
+        let items = self.repo.list(&filter, per_page, offset).await?;
+        let total = self.repo.count(&filter).await?;
+
+        Ok(SyncRunPage {
+            items,
+            total,
+            page,
+            per_page,
+            total_pages: (total + per_page - 1) / per_page,
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{
+    clients::IssPositionProvider,
+    domain::{
+        iss::{orbit::Sgp4, IssTrackPoint, ProviderPosition},
+        satellite::tle_cache_source,
+        solar::{is_sunlit, visibility_label},
+        ApiError,
+    },
+    repo::CacheRepo,
+    services::iss_service::parse_tle_payload,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::json;
+
+/// TLE старше этого (в часах) для расчёта текущей позиции не используется
+pub const TLE_PROVIDER_MAX_AGE_HOURS: f64 = 72.0;
+
+/// Расчёт позиции по SGP4 из закэшированного TLE, без сети.
+/// Для МКС - резерв, для остальных объектов - основной источник.
+pub struct TlePropagationProvider {
+    cache_repo: CacheRepo,
+}
+
+impl TlePropagationProvider {
+    pub fn new(cache_repo: CacheRepo) -> Self {
+        Self { cache_repo }
+    }
+}
+
+#[async_trait]
+impl IssPositionProvider for TlePropagationProvider {
+    fn name(&self) -> &'static str {
+        "tle"
+    }
+
+    /// Подходит для любого объекта, для которого загружен TLE
+    fn supports(&self, _norad_id: i32) -> bool {
+        true
+    }
+
+    fn source_url(&self, norad_id: i32) -> String {
+        format!("sgp4://space_cache/{}", tle_cache_source(norad_id))
+    }
+
+    async fn fetch_position(&self, norad_id: i32) -> Result<ProviderPosition, ApiError> {
+        let entry = self
+            .cache_repo
+            .get_latest(&tle_cache_source(norad_id))
+            .await?
+            .ok_or_else(|| ApiError::UpstreamError {
+                code: "TLE_UNAVAILABLE".to_string(),
+                message: format!("No cached TLE for NORAD {} to propagate", norad_id),
+            })?;
+        let tle = parse_tle_payload(&entry.payload)?;
+
+        let now = Utc::now();
+        let age_hours = tle.age_hours(now);
+        if age_hours.abs() > TLE_PROVIDER_MAX_AGE_HOURS {
+            return Err(ApiError::UpstreamError {
+                code: "TLE_STALE".to_string(),
+                message: format!("Cached TLE is {:.1} h old", age_hours),
+            });
+        }
+
+        let sgp4 = Sgp4::new(&tle)?;
+        let point = IssTrackPoint::propagate(&sgp4, now)?;
+        let sunlit = is_sunlit(sgp4.propagate_at(now)?.position, now);
+
+        Ok(ProviderPosition {
+            latitude: point.latitude,
+            longitude: point.longitude,
+            altitude: Some(point.altitude),
+            velocity: Some(point.velocity),
+            visibility: Some(visibility_label(sunlit).to_string()),
+            timestamp: now,
+            raw: json!({
+                "tle_epoch": tle.epoch,
+                "tle_age_hours": age_hours,
+                "line1": tle.line1,
+                "line2": tle.line2,
+            }),
+        })
+    }
+}